@@ -0,0 +1,148 @@
+use simple_logger::SimpleLogger;
+use wgpu::{
+    vertex_attr_array, BufferAddress, Device, PrimitiveTopology, ShaderModule,
+    ShaderModuleDescriptor, ShaderSource, VertexAttribute, VertexBufferLayout, VertexStepMode,
+};
+
+use LemoGUI::adapter::*;
+use LemoGUI::backend::wgpu_impl::*;
+use LemoGUI::graphic::base::*;
+use LemoGUI::graphic::style::*;
+use LemoGUI::instance::*;
+use LemoGUI::widget::*;
+
+fn main() {
+    SimpleLogger::new()
+        .with_level(log::LevelFilter::Info)
+        .init()
+        .unwrap();
+    Visualizer::run();
+}
+
+/// 波形顶点，每个采样点生成上下两个顶点
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct WaveVertex {
+    position: [f32; 2],
+    level: f32,
+}
+
+const WAVE_ATTRS: [VertexAttribute; 2] = vertex_attr_array![
+    0 => Float32x2,
+    1 => Float32
+];
+
+/// 波形着色器，拼接在全局参数着色器之后，按振幅由蓝到红着色
+const WAVE_SHADER: &str = r#"
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) level: f32,
+};
+
+@vertex
+fn vs_main(@location(0) position: vec2<f32>, @location(1) level: f32) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = to_clip(position);
+    out.level = level;
+    return out;
+}
+
+@fragment
+fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
+    let low = vec3<f32>(0.1, 0.4, 0.9);
+    let high = vec3<f32>(0.9, 0.2, 0.3);
+    return vec4<f32>(mix(low, high, input.level), 1.0);
+}
+"#;
+
+impl VertexLayout for WaveVertex {
+    fn set_vertex_desc<'a>() -> VertexBufferLayout<'a> {
+        VertexBufferLayout {
+            array_stride: std::mem::size_of::<WaveVertex>() as BufferAddress,
+            step_mode: VertexStepMode::Vertex,
+            attributes: &WAVE_ATTRS,
+        }
+    }
+
+    fn get_shape_type() -> ShapeType {
+        ShapeType::Custom("waveform")
+    }
+
+    fn get_topology() -> PrimitiveTopology {
+        PrimitiveTopology::TriangleStrip
+    }
+
+    fn get_shader(device: &Device) -> ShaderModule {
+        device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("waveform shader"),
+            source: ShaderSource::Wgsl(format!("{}{}", GLOBALS_SHADER, WAVE_SHADER).into()),
+        })
+    }
+}
+
+/// 波形图形，采样值范围为-1.0~1.0
+struct Waveform {
+    rect: Rectangle,
+    samples: Vec<f32>,
+}
+
+impl ShapeGraph for Waveform {
+    fn to_buffer(&self, gpu_context: &GPUContext, _style: Style) -> VBuffer {
+        let count = self.samples.len().max(2) - 1;
+        let half_height = self.rect.height as f32 / 2.0;
+        let center_y = self.rect.position.y + half_height;
+        let mut vertices = Vec::with_capacity(self.samples.len() * 2);
+        for (i, sample) in self.samples.iter().enumerate() {
+            let x = self.rect.position.x + self.rect.width as f32 * i as f32 / count as f32;
+            let amplitude = sample.clamp(-1.0, 1.0).abs().max(0.01) * half_height;
+            let level = sample.abs().min(1.0);
+            vertices.push(WaveVertex {
+                position: [x, center_y - amplitude],
+                level,
+            });
+            vertices.push(WaveVertex {
+                position: [x, center_y + amplitude],
+                level,
+            });
+        }
+        let indices: Vec<u16> = (0..vertices.len() as u16).collect();
+        VertexBuffer::create_vertex_buf(&gpu_context.device, vertices, &indices)
+    }
+}
+
+struct Visualizer;
+
+impl Instance for Visualizer {
+    type M = ();
+
+    fn new() -> Self {
+        Self
+    }
+
+    fn layout(&self) -> Panel<()> {
+        let samples = (0..256)
+            .map(|i| {
+                let t = i as f32 / 256.0;
+                (t * 40.0).sin() * (t * 3.0).sin()
+            })
+            .collect();
+        let waveform = Waveform {
+            rect: Rectangle::new(20.0, 20.0, 560, 200),
+            samples,
+        };
+        Panel::new().push(ShapeBoard::new(vec![Box::new(waveform)], Style::default()))
+    }
+
+    fn setting() -> Setting {
+        Setting {
+            size: Point::new(600., 240.),
+            ..Setting::default()
+        }
+    }
+
+    fn register_pipelines(gpu_context: &mut GPUContext) {
+        gpu_context
+            .register_pipeline::<WaveVertex>()
+            .expect("register wave pipeline");
+    }
+}
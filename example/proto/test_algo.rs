@@ -2,7 +2,9 @@ use cgmath::{Matrix4, Vector4};
 
 use crate::test_closure::*;
 
+#[allow(dead_code)]
 mod test_alter_message;
+#[allow(dead_code)]
 mod test_closure;
 
 fn main() {
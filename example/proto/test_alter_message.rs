@@ -1,4 +1,4 @@
-use winit::event::{KeyboardInput, VirtualKeyCode};
+use winit::event::VirtualKeyCode;
 
 /// a send message
 /// m broadcast message
@@ -26,7 +26,7 @@ use winit::event::{KeyboardInput, VirtualKeyCode};
 /// 事件类型枚举
 #[derive(Debug)]
 enum EventType {
-    mouse,
+    Mouse,
     KeyBoard(VirtualKeyCode),
 }
 
@@ -41,7 +41,7 @@ fn main() {
     use simple_logger::SimpleLogger;
     use winit::{
         event::{Event, WindowEvent},
-        event_loop::{ControlFlow, EventLoop},
+        event_loop::{ControlFlow, EventLoopBuilder},
         window::WindowBuilder,
     };
 
@@ -51,7 +51,7 @@ fn main() {
     }
 
     SimpleLogger::new().init().unwrap();
-    let event_loop = EventLoop::<CustomEvent>::with_user_event();
+    let event_loop = EventLoopBuilder::<CustomEvent>::with_user_event().build();
 
     let _window = WindowBuilder::new()
         .with_title("A fantastic window!")
@@ -7,7 +7,7 @@ pub fn indices() {
     while i < lens - 1 {
         ind.push(0);
         ind.push(i);
-        i = i + 1;
+        i += 1;
         ind.push(i);
     }
     println!("hello{:?}", ind);
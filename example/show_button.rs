@@ -2,6 +2,7 @@ use std::fmt::Debug;
 
 use simple_logger::SimpleLogger;
 
+use LemoGUI::event::Command;
 use LemoGUI::graphic::base::*;
 use LemoGUI::graphic::style::*;
 use LemoGUI::instance::*;
@@ -43,8 +44,23 @@ impl Instance for Counter {
         let style = Style::default()
             .border(RGBA(0.2, 0.2, 0.2, 0.5))
             .hover_color(RGBA(0.0, 0.75, 1.0, 0.5))
-            .back_color(RGBA(1.0, 0.5, 0.5, 1.0))
+            .back_gradient(
+                Gradient::linear(
+                    90.0,
+                    &[
+                        (0.0, RGBA(1.0, 0.5, 0.5, 1.0)),
+                        (1.0, RGBA(0.9, 0.3, 0.6, 1.0)),
+                    ],
+                )
+                .expect("invalid gradient"),
+            )
             .font_color(RGBA(0.1, 0.3, 0.8, 1.0))
+            .shadow(BoxShadow::new(
+                Point::new(0.0, 3.0),
+                8.0,
+                0.0,
+                RGBA(0.0, 0.0, 0.0, 0.35),
+            ))
             .round();
         let b1 = Button::new_with_style(rect, style, "数字 +").action(Ms::Add);
         Panel::new()
@@ -58,20 +74,22 @@ impl Instance for Counter {
             .push(Button::new(Point::new(230., 100.), self.value.to_string()))
     }
 
-    fn update(&mut self, broadcast: &Ms) {
+    fn update(&mut self, broadcast: &Ms) -> Command<Ms> {
         match broadcast {
             Ms::Add => self.value += 1,
             Ms::Sub => self.value -= 1,
             Ms::Text(str) => self.text = str.to_string(),
         }
+        Command::none()
     }
 
     fn setting() -> Setting {
         log::info!("build window");
 
-        let mut setting = Setting::default();
-        setting.size = Point::new(428., 433.);
-        setting.icon_path = Some(concat!(env!("CARGO_MANIFEST_DIR"), "/res/icon.png").into());
-        setting
+        Setting {
+            size: Point::new(428., 433.),
+            icon_path: Some(concat!(env!("CARGO_MANIFEST_DIR"), "/res/icon.png").into()),
+            ..Setting::default()
+        }
     }
 }
@@ -48,17 +48,52 @@ impl Instance for Board {
         shapes.push(Box::new(polygon));
         shapes.push(Box::new(points));
         shapes.push(Box::new(rects));
+        // 连接线及曲线
+        let line = Line::new(
+            Point::new(242., 86.),
+            Point::new(291., 160.),
+            Stroke::new(3.).cap(LineCap::Round),
+        );
+        let polyline = Polyline::new(
+            vec![
+                Point::new(131., 292.),
+                Point::new(131., 360.),
+                Point::new(331., 360.),
+                Point::new(331., 460.),
+            ],
+            Stroke::new(2.).join(LineJoin::Round).dash(&[8., 4.], 0.),
+        );
+        let curve = Path::new()
+            .move_to(Point::new(21., 620.))
+            .cubic_to(
+                Point::new(200., 520.),
+                Point::new(400., 720.),
+                Point::new(600., 620.),
+            )
+            .arc_to(
+                Point::new(630., 620.),
+                Point::new(30., 30.),
+                std::f32::consts::PI,
+            )
+            .stroke(Stroke::new(4.).cap(LineCap::Round).join(LineJoin::Round));
+        shapes.push(Box::new(line));
+        shapes.push(Box::new(polyline));
+        shapes.push(Box::new(curve));
         let style = Style::default().back_color(LIGHT_BLUE);
-        Panel::new().push(ShapeBoard {
-            shape_arr: shapes,
-            style,
-        })
+        // 可在窗口中用鼠标手绘线条
+        Panel::new().push(
+            ShapeBoard::new(shapes, style)
+                .editable(Rectangle::new(0.0, 0.0, 428, 633))
+                .tool(BoardTool::Freehand)
+                .item_style(Style::default().back_color(RGBA(0.2, 0.2, 0.2, 1.0))),
+        )
     }
 
     fn setting() -> Setting {
         log::info!("build window");
-        let mut setting = Setting::default();
-        setting.size = Point::new(428., 633.);
-        setting
+        Setting {
+            size: Point::new(428., 633.),
+            ..Setting::default()
+        }
     }
 }
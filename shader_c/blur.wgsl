@@ -0,0 +1,63 @@
+
+struct VertexInput {
+    @location(0) pos: vec2<f32>,
+    @location(1) size: vec2<f32>,
+    @location(2) radii: vec4<f32>,
+    @location(3) blur: f32,
+    @builtin(vertex_index) gl_VertexIndex: u32,
+};
+
+struct VertexOutput {
+    @builtin(position) gl_Position: vec4<f32>,
+    @location(0) center: vec2<f32>,
+    @location(1) half_size: vec2<f32>,
+    @location(2) radii: vec4<f32>,
+    @location(3) blur: f32,
+};
+
+@vertex
+fn vs_main(input: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+
+    out.gl_Position = fullscreen_quad(input.gl_VertexIndex);
+    out.center = input.pos + input.size / 2.0;
+    out.half_size = input.size / 2.0;
+    out.radii = input.radii;
+    out.blur = input.blur;
+
+    return out;
+}
+
+@group(1) @binding(0)
+var t_backdrop: texture_2d<f32>;
+@group(1) @binding(1)
+var s_backdrop: sampler;
+
+// samples on each side of the kernel center
+const KERNEL_STEPS: i32 = 4;
+
+@fragment
+fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
+    let p = input.gl_Position.xy;
+    let local = to_local(p);
+    let inside = coverage(sd_round_box(local - input.center, input.half_size, input.radii) * transform_scale());
+    if (inside <= 0.0) {
+        return vec4<f32>(0.0);
+    }
+    let dims = vec2<f32>(textureDimensions(t_backdrop));
+    let blur = input.blur * transform_scale();
+    let sigma = max(blur / 2.0, 0.5);
+    let step = blur / f32(KERNEL_STEPS);
+    var sum = vec4<f32>(0.0);
+    var total = 0.0;
+    for (var i: i32 = -KERNEL_STEPS; i <= KERNEL_STEPS; i = i + 1) {
+        for (var j: i32 = -KERNEL_STEPS; j <= KERNEL_STEPS; j = j + 1) {
+            let o = vec2<f32>(f32(i), f32(j)) * step;
+            let w = exp(-dot(o, o) / (2.0 * sigma * sigma));
+            sum = sum + textureSampleLevel(t_backdrop, s_backdrop, (p + o) / dims, 0.0) * w;
+            total = total + w;
+        }
+    }
+    let color = sum / total;
+    return vec4<f32>(color.rgb, inside);
+}
@@ -1,18 +1,30 @@
 
 struct VertexInput {
 	@location(0) pos: vec2<f32>,
-	@location(1) color: vec4<f32>,
-	@location(2) radius: f32,
-	@location(3) edge: u32,
+	@location(1) radius: f32,
+	@location(2) edge: u32,
+	@location(3) kind: vec2<u32>,
+	@location(4) params: vec4<f32>,
+	@location(5) offsets: vec4<f32>,
+	@location(6) c0: vec4<f32>,
+	@location(7) c1: vec4<f32>,
+	@location(8) c2: vec4<f32>,
+	@location(9) c3: vec4<f32>,
 	@builtin(vertex_index) gl_VertexIndex: u32,
 };
 
 struct VertexOutput {
     @builtin(position) gl_Position: vec4<f32>,
 	@location(0) pos: vec2<f32>,
-    @location(1) color: vec4<f32>,
-    @location(2) radius: f32,
-    @location(3) edge: f32,
+    @location(1) radius: f32,
+    @location(2) edge: f32,
+    @location(3) @interpolate(flat) kind: vec2<u32>,
+    @location(4) params: vec4<f32>,
+    @location(5) offsets: vec4<f32>,
+    @location(6) c0: vec4<f32>,
+    @location(7) c1: vec4<f32>,
+    @location(8) c2: vec4<f32>,
+    @location(9) c3: vec4<f32>,
 };
 // var gl_VertexIndex: i32;
 
@@ -21,7 +33,13 @@ fn vs_main(input: VertexInput) -> VertexOutput  {
 	var out: VertexOutput;
 
     out.pos = input.pos;
-    out.color = input.color;
+    out.kind = input.kind;
+    out.params = input.params;
+    out.offsets = input.offsets;
+    out.c0 = input.c0;
+    out.c1 = input.c1;
+    out.c2 = input.c2;
+    out.c3 = input.c3;
     out.radius = input.radius;
     out.edge = f32(input.edge);
 
@@ -39,11 +57,9 @@ fn vs_main(input: VertexInput) -> VertexOutput  {
 
 fn circle(uvs: vec2<f32>, pos: vec2<f32>, rad: f32) -> f32 {
     var d: f32;
-    var t: f32;
 
-    d = (length(pos - uvs) - rad);
-    t = clamp(d, 0.0, 1.0);
-    return (1.0 - t);
+    d = (length(pos - uvs) - rad) * transform_scale();
+    return clamp(0.5 - d, 0.0, 1.0);
 }
 
 fn polygon(uvs1:vec2<f32>, pos1:vec2<f32>, radius: f32, n: f32) -> f32 {
@@ -56,7 +72,9 @@ fn polygon(uvs1:vec2<f32>, pos1:vec2<f32>, radius: f32, n: f32) -> f32 {
     angle = atan2((uvs1[0u] - pos1[0u]), ( uvs1[1u] - pos1[1u]));
     r = (6.28318530718 / n);
     b = (cos(((floor((0.5 + (angle / r))) * r) - angle)) * d1);
-    return (1.0 - step(0.8 , b));
+    // smooth the edge over one pixel, b grows by 1/radius per pixel
+    let w = 0.5 / (radius * transform_scale());
+    return (1.0 - smoothstep(0.8 - w, 0.8 + w, b));
 }
 
 
@@ -64,14 +82,15 @@ fn polygon(uvs1:vec2<f32>, pos1:vec2<f32>, radius: f32, n: f32) -> f32 {
 fn fs_main(input: VertexOutput) -> @location(0) vec4<f32>  {
 
     var intensity: f32;
+    let local = to_local(input.gl_Position.xy);
 
     if ((input.edge > 2.0)) {
-        intensity = polygon(input.gl_Position.xy, input.pos, input.radius, input.edge);
+        intensity = polygon(local, input.pos, input.radius, input.edge);
     } else {
-        intensity = circle(input.gl_Position.xy, input.pos, input.radius);
+        intensity = circle(local, input.pos, input.radius);
     }
-    if ((intensity > 0.0)) {
-         return input.color;
-    }
-   return vec4<f32>(0.0);
-}
\ No newline at end of file
+    let paint = make_paint(input.kind, input.params, input.offsets,
+                           input.c0, input.c1, input.c2, input.c3);
+    let color = paint_color(paint, local);
+    return vec4<f32>(color.rgb, color.a * intensity);
+}
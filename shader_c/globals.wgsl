@@ -0,0 +1,28 @@
+// shared per draw uniforms, concatenated in front of every shader
+// transform maps local logical positions to framebuffer pixels (including the window
+// scale factor), inverse maps them back
+struct Globals {
+    transform: mat4x4<f32>,
+    inverse: mat4x4<f32>,
+    viewport: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> globals: Globals;
+
+// local pixel position to clip space
+fn to_clip(p: vec2<f32>) -> vec4<f32> {
+    let t = (globals.transform * vec4<f32>(p, 0.0, 1.0)).xy;
+    return vec4<f32>(2.0 * t.x / globals.viewport.x - 1.0, 1.0 - 2.0 * t.y / globals.viewport.y, 0.0, 1.0);
+}
+
+// framebuffer pixel to local pixel position
+fn to_local(p: vec2<f32>) -> vec2<f32> {
+    return (globals.inverse * vec4<f32>(p, 0.0, 1.0)).xy;
+}
+
+// framebuffer pixels per local unit, keeps anti-aliased edges one pixel wide
+fn transform_scale() -> f32 {
+    let t = globals.transform;
+    return sqrt(abs(t[0].x * t[1].y - t[0].y * t[1].x));
+}
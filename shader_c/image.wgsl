@@ -3,6 +3,7 @@ struct VertexInput {
 	@location(0) pos: vec2<f32>,
 	@location(1) size: vec2<f32>,
 	@location(2) color: vec4<f32>,
+	@location(3) mode: u32,
 	@builtin(vertex_index) gl_VertexIndex: u32,
 };
 
@@ -10,6 +11,7 @@ struct VertexOutput {
     @builtin(position) gl_Position: vec4<f32>,
 	@location(0) v_tex_coords: vec2<f32>,
 	@location(1) color: vec4<f32>,
+	@location(2) @interpolate(flat) mode: u32,
 };
 // var gl_VertexIndex: i32;
 
@@ -27,21 +29,23 @@ fn vs_main(input: VertexInput) -> VertexOutput  {
     var pos: vec2<f32> = input.pos;
     var size: vec2<f32> = input.size;
 
+    // pos is the top left corner in pixels
     var positions: array<vec2<f32>,4u> =
                     array<vec2<f32>,4u>(pos,
                             vec2<f32>((pos[0u]+ size[0u]), pos[1u]),
-                            vec2<f32>(pos[0u], (pos[1u] - size[1u])),
-                            vec2<f32>((pos[0u] + size[0u]), (pos[1u] - size[1u])));
+                            vec2<f32>(pos[0u], (pos[1u] + size[1u])),
+                            vec2<f32>((pos[0u] + size[0u]), (pos[1u] + size[1u])));
     let coord: vec2<f32> = positions[gl_VertexIndex];
-    out.gl_Position = vec4<f32>(coord, 0.0, 1.0);
+    out.gl_Position = to_clip(coord);
 	out.v_tex_coords = tex_coords[gl_VertexIndex];
     out.color = input.color;
+    out.mode = input.mode;
     return out;
 }
 
-@group(0) @binding(0)
+@group(1) @binding(0)
 var t_diffuse: texture_2d<f32>;
-@group(0) @binding(1)
+@group(1) @binding(1)
 var s_diffuse: sampler;
 
 @fragment
@@ -51,6 +55,18 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
     //texColor = textureSample(t_diffuse, s_diffuse, input.v_tex_coords) * vec4<f32>(1.0, 1.0, 1.0, 1.0);
     //return texColor;
     let color = input.color;
-    return vec4<f32>(color.x,color.y,color.z,textureSample(t_diffuse, s_diffuse, input.v_tex_coords).x);
+    let tex_color = textureSample(t_diffuse, s_diffuse, input.v_tex_coords);
+    // mode 1: rgba image
+    if (input.mode == 1u) {
+        return tex_color * color;
+    }
+    // mode 2: offscreen layer holding premultiplied colors
+    if (input.mode == 2u) {
+        if (tex_color.a <= 0.0) {
+            return vec4<f32>(0.0);
+        }
+        return vec4<f32>(tex_color.rgb / tex_color.a, tex_color.a) * color;
+    }
+    return vec4<f32>(color.x,color.y,color.z,tex_color.x);
 
 }
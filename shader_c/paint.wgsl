@@ -0,0 +1,41 @@
+// shared paint evaluation, concatenated in front of the shape shaders
+// kind.x: 0 solid, 1 linear gradient, 2 radial gradient; kind.y: stop count
+// params: linear start and end point, or radial center and radius, in pixels
+struct Paint {
+    kind: vec2<u32>,
+    params: vec4<f32>,
+    offsets: vec4<f32>,
+    colors: array<vec4<f32>, 4>,
+};
+
+fn make_paint(kind: vec2<u32>, params: vec4<f32>, offsets: vec4<f32>,
+              c0: vec4<f32>, c1: vec4<f32>, c2: vec4<f32>, c3: vec4<f32>) -> Paint {
+    return Paint(kind, params, offsets, array<vec4<f32>, 4>(c0, c1, c2, c3));
+}
+
+fn paint_color(paint: Paint, frag: vec2<f32>) -> vec4<f32> {
+    var colors = paint.colors;
+    if (paint.kind.x == 0u || paint.kind.y == 0u) {
+        return colors[0];
+    }
+    var t: f32;
+    if (paint.kind.x == 1u) {
+        let dir = paint.params.zw - paint.params.xy;
+        t = dot(frag - paint.params.xy, dir) / max(dot(dir, dir), 0.0001);
+    } else {
+        t = length(frag - paint.params.xy) / max(paint.params.z, 0.0001);
+    }
+    let count = i32(min(paint.kind.y, 4u));
+    if (t <= paint.offsets[0]) {
+        return colors[0];
+    }
+    for (var i: i32 = 1; i < count; i = i + 1) {
+        let end = paint.offsets[i];
+        if (t <= end) {
+            let start = paint.offsets[i - 1];
+            let f = (t - start) / max(end - start, 0.0001);
+            return mix(colors[i - 1], colors[i], f);
+        }
+    }
+    return colors[count - 1];
+}
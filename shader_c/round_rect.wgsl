@@ -3,17 +3,31 @@ struct VertexInput {
 	@location(0) size: vec2<f32>,
     @location(1) pos: vec2<f32>,
     @location(2) borderColor: vec4<f32>,
-    @location(3) rectColor: vec4<f32>,
-    @location(4) is_round_or_border: vec2<u32>,
+    @location(3) radii: vec4<f32>,
+    @location(4) border: vec3<f32>,
+    @location(5) kind: vec2<u32>,
+    @location(6) params: vec4<f32>,
+    @location(7) offsets: vec4<f32>,
+    @location(8) c0: vec4<f32>,
+    @location(9) c1: vec4<f32>,
+    @location(10) c2: vec4<f32>,
+    @location(11) c3: vec4<f32>,
     @builtin(vertex_index) gl_VertexIndex: u32,
 };
 
 struct VertexOutput {
 	@location(0) pos: vec2<f32>,
     @location(1) size: vec2<f32>,
-    @location(2) is_round_or_border: vec2<u32>,
+    @location(2) radii: vec4<f32>,
     @location(3) borderColor: vec4<f32>,
-    @location(4) rectColor: vec4<f32>,
+    @location(4) border: vec3<f32>,
+    @location(5) @interpolate(flat) kind: vec2<u32>,
+    @location(6) params: vec4<f32>,
+    @location(7) offsets: vec4<f32>,
+    @location(8) c0: vec4<f32>,
+    @location(9) c1: vec4<f32>,
+    @location(10) c2: vec4<f32>,
+    @location(11) c3: vec4<f32>,
     @builtin(position) gl_Position: vec4<f32>,
 };
 // var gl_VertexIndex: i32;
@@ -37,56 +51,45 @@ fn vs_main(input: VertexInput) -> VertexOutput  {
 	out.pos = vec2<f32>(start_x + width / 2.0, start_y + height / 2.0);
 	out.size = vec2<f32>(width, height);
 	out.borderColor = input.borderColor;
-	out.rectColor = input.rectColor;
-	out.is_round_or_border = input.is_round_or_border;
+	out.kind = input.kind;
+	out.params = input.params;
+	out.offsets = input.offsets;
+	out.c0 = input.c0;
+	out.c1 = input.c1;
+	out.c2 = input.c2;
+	out.c3 = input.c3;
+	out.radii = input.radii;
+	out.border = input.border;
 
     return out;
 }
 
-fn rect(uvs: vec2<f32>, pos: vec2<f32>, size: vec2<f32>, rad: f32) -> f32{
-    var alpha: f32 = 0.0;
-    let p_a: vec2<f32> = pos - size/2.0;
-    let p_b: vec2<f32> = pos + size/2.0;
-    let p_ar: vec2<f32> = p_a + vec2<f32>(rad);
-    let p_br: vec2<f32> = p_b - vec2<f32>(rad);
-    let p_a1: vec2<f32> = vec2<f32>(p_a.x + rad, p_b.y - rad);
-    let p_b1: vec2<f32> = vec2<f32>(p_b.x - rad, p_a.y + rad);
-    if (all(p_a < uvs)&&all(uvs < p_b)){
-       alpha = 1.0;
-       if((all(uvs < p_ar)&&distance(uvs,p_ar)>rad)
-           ||(all(uvs > p_br)&&distance(uvs,p_br)>rad)
-           ||((uvs.x<p_a1.x&&uvs.y>p_a1.y)&&(distance(uvs,p_a1)>rad))
-           ||((uvs.x>p_b1.x&&uvs.y<p_b1.y)&&(distance(uvs,p_b1)>rad))){
-           alpha=0.0;
-       }
-    }
-    return alpha;
+// whether a point of the border falls on a dash, measured along the nearest edge
+fn on_dash(local: vec2<f32>, size: vec2<f32>, dash: f32, gap: f32) -> bool {
+    let dx = min(local.x, size.x - local.x);
+    let dy = min(local.y, size.y - local.y);
+    let along = select(local.x, local.y, dx < dy);
+    return along % (dash + gap) < dash;
 }
+
 @fragment
 fn fs_main(input: VertexOutput) -> @location(0) vec4<f32>  {
-	var frameAlpha: f32;
-	var size: vec2<f32>;
-	var radius: f32;
-	var border: f32 = 0.0;
-	var fragColor: vec4<f32> = vec4<f32>(0.0);
-	var borderColor: vec4<f32> = input.rectColor;
-    size = input.size;
+	let border_width: f32 = input.border.x;
+	let paint = make_paint(input.kind, input.params, input.offsets,
+	                       input.c0, input.c1, input.c2, input.c3);
+	let local = to_local(input.gl_Position.xy);
+	let rectColor = paint_color(paint, local);
+	var fragColor: vec4<f32> = rectColor;
 
-	if (input.is_round_or_border[0u]==0u){
-	    radius = 0.0;
-	}else{
-	    radius = 8.0;
-	}
-	if (input.is_round_or_border[1u]==1u){
-		border = rect(input.gl_Position.xy,input.pos ,size - vec2<f32>(1.0), radius);
-		borderColor = input.borderColor;
+	let p = local - input.pos;
+	let scale = transform_scale();
+	let d = sd_round_box(p, input.size / 2.0, input.radii) * scale;
+	if (border_width > 0.0) {
+		var borderColor = input.borderColor;
+		if (input.border.y > 0.0 && !on_dash(p + input.size / 2.0, input.size, input.border.y, input.border.z)) {
+			borderColor = rectColor;
+		}
+		fragColor = mix(borderColor, rectColor, coverage(d + border_width * scale));
 	}
-
-	frameAlpha = rect(input.gl_Position.xy,input.pos ,size ,radius);
-	if (frameAlpha > 0.0&&border==0.0){
-	    fragColor = borderColor;
-    } else if(frameAlpha > 0.0&&border>0.0){
-   	    fragColor = input.rectColor;
-    }
-    return fragColor;
-}
\ No newline at end of file
+	return vec4<f32>(fragColor.rgb, fragColor.a * coverage(d));
+}
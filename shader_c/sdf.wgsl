@@ -0,0 +1,30 @@
+// shared signed distance helpers, concatenated in front of the shape shaders
+
+// signed distance to a rounded box centered at the origin, y axis points down
+// radii: top left, top right, bottom right, bottom left
+fn sd_round_box(p: vec2<f32>, half_size: vec2<f32>, radii: vec4<f32>) -> f32 {
+    var rad: f32;
+    if (p.x > 0.0) {
+        rad = select(radii.y, radii.z, p.y > 0.0);
+    } else {
+        rad = select(radii.x, radii.w, p.y > 0.0);
+    }
+    let r = min(rad, min(half_size.x, half_size.y));
+    let q = abs(p) - half_size + vec2<f32>(r);
+    return length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0) - r;
+}
+
+// pixel coverage of a signed distance, gives a one pixel wide smooth edge
+fn coverage(d: f32) -> f32 {
+    return clamp(0.5 - d, 0.0, 1.0);
+}
+
+// fullscreen quad, the fragment shader decides the coverage of every pixel
+fn fullscreen_quad(index: u32) -> vec4<f32> {
+    var positions: array<vec2<f32>,4u> =
+                        array<vec2<f32>,4u>(vec2<f32>(-1.0, 1.0),
+                                            vec2<f32>(1.0, 1.0),
+                                            vec2<f32>(-1.0, -1.0),
+                                            vec2<f32>(1.0, -1.0));
+    return vec4<f32>(positions[index], 0.0, 1.0);
+}
@@ -0,0 +1,68 @@
+
+struct VertexInput {
+    @location(0) pos: vec2<f32>,
+    @location(1) size: vec2<f32>,
+    @location(2) offset: vec2<f32>,
+    @location(3) color: vec4<f32>,
+    @location(4) radii: vec4<f32>,
+    @location(5) blur: f32,
+    @location(6) spread: f32,
+    @builtin(vertex_index) gl_VertexIndex: u32,
+};
+
+struct VertexOutput {
+    @builtin(position) gl_Position: vec4<f32>,
+    @location(0) center: vec2<f32>,
+    @location(1) half_size: vec2<f32>,
+    @location(2) offset: vec2<f32>,
+    @location(3) color: vec4<f32>,
+    @location(4) radii: vec4<f32>,
+    @location(5) blur: f32,
+    @location(6) spread: f32,
+};
+
+@vertex
+fn vs_main(input: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+
+    out.gl_Position = fullscreen_quad(input.gl_VertexIndex);
+    out.center = input.pos + input.size / 2.0;
+    out.half_size = input.size / 2.0;
+    out.offset = input.offset;
+    out.color = input.color;
+    out.radii = input.radii;
+    out.blur = input.blur;
+    out.spread = input.spread;
+
+    return out;
+}
+
+// approximation of the error function, max error 5e-4
+fn erf(x: f32) -> f32 {
+    let s = sign(x);
+    let a = abs(x);
+    var r = 1.0 + (0.278393 + (0.230389 + 0.078108 * (a * a)) * a) * a;
+    r = r * r;
+    return s - s / (r * r);
+}
+
+@fragment
+fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
+    let p = to_local(input.gl_Position.xy) - input.center;
+    let scale = transform_scale();
+    // rounded corners grow with the spread, square corners stay square
+    let radii = select(max(input.radii + vec4<f32>(input.spread), vec4<f32>(0.0)), input.radii,
+                       input.radii <= vec4<f32>(0.0));
+    let d = sd_round_box(p - input.offset, input.half_size + vec2<f32>(input.spread), radii);
+    var alpha: f32;
+    if (input.blur > 0.0) {
+        // a gaussian blurred edge, the blur radius covers two standard deviations
+        let sigma = input.blur / 2.0;
+        alpha = 0.5 - 0.5 * erf(d / (sigma * 1.41421356));
+    } else {
+        alpha = coverage(d * scale);
+    }
+    // the shadow is only visible outside of the shape itself
+    let inside = coverage(sd_round_box(p, input.half_size, input.radii) * scale);
+    return vec4<f32>(input.color.rgb, input.color.a * alpha * (1.0 - inside));
+}
@@ -1,25 +1,46 @@
-
 struct VertexInput {
     @location(0) a_position: vec2<f32>,
-    @location(1) a_color: vec4<f32>,
+    @location(1) kind: vec2<u32>,
+    @location(2) params: vec4<f32>,
+    @location(3) offsets: vec4<f32>,
+    @location(4) c0: vec4<f32>,
+    @location(5) c1: vec4<f32>,
+    @location(6) c2: vec4<f32>,
+    @location(7) c3: vec4<f32>,
 };
 
 struct VertexOutput {
     @builtin(position) position: vec4<f32>,
-    @location(0) v_color: vec4<f32>,
+    @location(0) @interpolate(flat) kind: vec2<u32>,
+    @location(1) @interpolate(flat) params: vec4<f32>,
+    @location(2) @interpolate(flat) offsets: vec4<f32>,
+    @location(3) @interpolate(flat) c0: vec4<f32>,
+    @location(4) @interpolate(flat) c1: vec4<f32>,
+    @location(5) @interpolate(flat) c2: vec4<f32>,
+    @location(6) @interpolate(flat) c3: vec4<f32>,
+    @location(7) local: vec2<f32>,
 };
 
 @vertex
 fn vs_main(input: VertexInput) -> VertexOutput {
     var out: VertexOutput;
 
-    out.v_color = input.a_color;
-    out.position = vec4<f32>(input.a_position, 0.0, 1.0);
+    out.kind = input.kind;
+    out.params = input.params;
+    out.offsets = input.offsets;
+    out.c0 = input.c0;
+    out.c1 = input.c1;
+    out.c2 = input.c2;
+    out.c3 = input.c3;
+    out.local = input.a_position;
+    out.position = to_clip(input.a_position);
 
     return out;
 }
 
 @fragment
 fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
-    return input.v_color;
-}
\ No newline at end of file
+    let paint = make_paint(input.kind, input.params, input.offsets,
+                           input.c0, input.c1, input.c2, input.c3);
+    return paint_color(paint, input.local);
+}
@@ -1,5 +1,3 @@
-use crate::event::EventContext;
-
 /// 图形渲染采用wgpu实现
 #[cfg(feature = "wgpu_impl")]
 pub type GPUContext = crate::backend::wgpu_impl::WGPUContext;
@@ -25,14 +23,14 @@ pub struct DisplayWindow<M: 'static> {
     /// 图形上下文
     pub gpu_context: GPUContext,
     /// 时间监听器
-    pub(crate) event_loop: winit::event_loop::EventLoop<M>,
+    pub(crate) event_loop: winit::event_loop::EventLoop<crate::event::Envelope<M>>,
     /// 事件上下文
     pub(crate) event_context: crate::backend::winit_impl::WEventContext<M>,
     /// 字体缓冲
     pub font_map: crate::graphic::base::GCharMap,
 }
 
-impl<M: 'static + std::fmt::Debug> DisplayWindow<M> {
+impl<M: 'static + Clone + Send + std::fmt::Debug> DisplayWindow<M> {
     pub fn start<C>(self, container: C)
     where
         C: crate::widget::ComponentModel<M> + 'static,
@@ -40,7 +38,7 @@ impl<M: 'static + std::fmt::Debug> DisplayWindow<M> {
         run_instance(self, container);
     }
 
-    pub fn new<'a>(setting: crate::instance::Setting) -> DisplayWindow<M> {
+    pub fn new(setting: crate::instance::Setting) -> DisplayWindow<M> {
         init_window(setting)
     }
 }
@@ -49,7 +47,7 @@ impl<M: 'static + std::fmt::Debug> DisplayWindow<M> {
 fn run_instance<C, M>(window: DisplayWindow<M>, container: C)
 where
     C: crate::widget::ComponentModel<M> + 'static,
-    M: 'static + std::fmt::Debug,
+    M: 'static + Clone + Send + std::fmt::Debug,
 {
     // 使用winit进行事件监听
     #[cfg(feature = "winit_impl")]
@@ -60,7 +58,7 @@ where
 }
 
 /// 初始化窗口方法
-fn init_window<M: 'static + std::fmt::Debug>(
+fn init_window<M: 'static + Send + std::fmt::Debug>(
     setting: crate::instance::Setting,
 ) -> DisplayWindow<M> {
     use futures::executor::block_on;
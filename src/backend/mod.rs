@@ -1,3 +1,5 @@
+/// 各窗口后端共用的事件运行时
+pub mod runtime;
 /// sdl2事件绑定
 #[cfg(feature = "sdl2_impl")]
 pub mod sdl2_impl;
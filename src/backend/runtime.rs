@@ -0,0 +1,498 @@
+use std::fmt::Debug;
+use std::time::Instant;
+
+use crate::adapter::GPUContext;
+use crate::event::*;
+use crate::graphic::animation::next_frame;
+use crate::graphic::base::{GCharMap, Point};
+use crate::widget::ComponentModel;
+
+/// 消息发送器，可在后台线程中将消息信封投递回事件循环
+pub trait MessageSender<M>: Send + 'static {
+    /// 投递消息信封，事件循环已关闭时返回错误
+    fn send(&self, envelope: Envelope<M>) -> Result<(), String>;
+}
+
+/// 窗口后端接口，由winit、sdl2等窗口库实现，只负责与窗口直接相关的操作
+pub trait WindowBackend<M>: 'static {
+    type Sender: MessageSender<M>;
+    /// 创建消息发送器
+    fn sender(&self) -> Self::Sender;
+    /// 切换鼠标图标
+    fn set_cursor(&mut self, cursor: Cursor);
+    /// 设置输入法候选框位置（逻辑坐标）
+    fn set_ime_position(&mut self, position: Point<f32>);
+}
+
+/// 事件运行时
+/// 与窗口库无关的事件处理逻辑：事件消费、指针捕获、悬停仲裁、消息路由、
+/// 异步命令、订阅、定时器、手势识别及截图，各窗口后端的事件上下文均由此实现
+pub struct Runtime<M: 'static, B> {
+    /// 窗口后端
+    pub(crate) backend: B,
+    /// 鼠标位置（逻辑坐标）
+    cursor_pos: Point<f32>,
+    /// 窗口缩放比例
+    scale_factor: f32,
+    /// 定时器队列
+    timers: TimerQueue<M>,
+    /// 正在运行的订阅
+    subscriptions: SubscriptionTracker,
+    /// 窗口事件
+    window_event: Option<GEvent>,
+    /// 当前事件是否已被组件处理
+    consumed: bool,
+    /// 指针是否被组件捕获
+    pointer_captured: bool,
+    /// 当前事件中是否已有组件处于悬停状态
+    hover_claimed: bool,
+    /// 当前显示的鼠标图标
+    cursor: Cursor,
+    /// 当前鼠标事件中组件请求的鼠标图标
+    cursor_request: Option<Cursor>,
+    /// 待保存的截图路径
+    capture_requests: Vec<String>,
+    /// 手势识别器
+    gestures: GestureRecognizer,
+    /// 自定义事件
+    message: Option<M>,
+    /// 自定义事件的目标实例
+    message_target: Option<InstanceId>,
+    /// 当前处理事件的实例，发送的消息以其为目标
+    route: Option<InstanceId>,
+    /// 是否有动画帧需要绘制，动画结束后仍保持到绘制完最终帧
+    animating: bool,
+    /// 下一次唤醒时刻，用于驱动动画、定时器及长按检查
+    wake: Option<Instant>,
+}
+
+impl<M: 'static, B: WindowBackend<M>> Runtime<M, B> {
+    pub fn new(backend: B, scale_factor: f32) -> Runtime<M, B> {
+        Runtime {
+            backend,
+            cursor_pos: Point::new(-1.0, -1.0),
+            scale_factor,
+            timers: TimerQueue::new(),
+            subscriptions: SubscriptionTracker::new(),
+            window_event: None,
+            consumed: false,
+            pointer_captured: false,
+            hover_claimed: false,
+            cursor: Cursor::Default,
+            cursor_request: None,
+            capture_requests: Vec::new(),
+            gestures: GestureRecognizer::new(),
+            message: None,
+            message_target: None,
+            route: None,
+            animating: false,
+            wake: None,
+        }
+    }
+
+    /// 下一次唤醒时刻
+    pub fn wake(&self) -> Option<Instant> {
+        self.wake
+    }
+
+    /// 接收消息信封
+    fn receive(&mut self, envelope: Envelope<M>) {
+        self.message = Some(envelope.message);
+        self.message_target = envelope.target;
+    }
+
+    /// 应用组件请求的鼠标图标，没有组件请求时恢复默认图标
+    fn apply_cursor(&mut self) {
+        let cursor = self.cursor_request.take().unwrap_or(Cursor::Default);
+        if cursor != self.cursor {
+            self.backend.set_cursor(cursor);
+            self.cursor = cursor;
+        }
+    }
+
+    /// 通过后端发送消息信封，失败时记录错误
+    fn send(&self, envelope: Envelope<M>) {
+        if let Err(error) = self.backend.sender().send(envelope) {
+            log::error!("failed to send message: {}", error);
+        }
+    }
+
+    /// 在后台运行新启动的订阅，消息发送给当前处理事件的实例
+    fn start_streams(&self, streams: Vec<SubscriptionStream<M>>)
+    where
+        M: Send,
+    {
+        let target = self.route;
+        for stream in streams {
+            let sender = self.backend.sender();
+            spawn_stream(stream, move |message| {
+                if let Err(error) = sender.send(Envelope { target, message }) {
+                    log::error!("failed to send subscription message: {}", error);
+                }
+            });
+        }
+    }
+}
+
+impl<M: Send + Debug + 'static, B: WindowBackend<M>> Runtime<M, B> {
+    /// 事件循环开始前将空事件交给容器处理，使其完成定时器、订阅等初始注册
+    pub fn start<C: ComponentModel<M> + 'static>(&mut self, container: &mut C) {
+        self.set_event(GEvent {
+            event: EventType::Other,
+            state: State::None,
+        });
+        container.listener(self);
+        self.wake = self.next_wake();
+    }
+
+    /// 将事件交给容器处理，并分发由其识别出的手势，需要重绘时返回true
+    /// 鼠标松开时，在分发完手势后释放指针捕获
+    pub fn dispatch_event<C: ComponentModel<M> + 'static>(
+        &mut self,
+        container: &mut C,
+        event: GEvent,
+    ) -> bool {
+        let released = matches!(event.event, EventType::Mouse(_)) && event.state == State::Released;
+        let pointer = event.is_pointer();
+        if pointer {
+            self.cursor_request = None;
+        }
+        let gestures = self
+            .gestures
+            .handle(&event, self.cursor_pos, Instant::now());
+        self.set_event(event);
+        let mut redraw = container.listener(self);
+        for gesture in gestures {
+            self.set_event(GEvent {
+                event: EventType::Gesture(gesture),
+                state: State::None,
+            });
+            if container.listener(self) {
+                redraw = true;
+            }
+        }
+        if released {
+            self.release_pointer();
+        }
+        if pointer {
+            self.apply_cursor();
+        }
+        redraw
+    }
+
+    /// 将事件交给容器处理，需要时重绘窗口
+    pub fn handle_event<C: ComponentModel<M> + 'static>(
+        &mut self,
+        gpu_context: &mut GPUContext,
+        container: &mut C,
+        font_map: &mut GCharMap,
+        event: GEvent,
+    ) {
+        if self.dispatch_event(container, event) {
+            gpu_context.present(container, font_map)
+        }
+    }
+
+    /// 将消息交给容器处理并重绘，异步任务的结果也由此送达
+    /// 消息为None时表示收到了无法识别的自定义事件，只重绘窗口
+    pub fn handle_message<C: ComponentModel<M> + 'static>(
+        &mut self,
+        gpu_context: &mut GPUContext,
+        container: &mut C,
+        font_map: &mut GCharMap,
+        envelope: Option<Envelope<M>>,
+    ) {
+        match envelope {
+            Some(envelope) => self.receive(envelope),
+            None => self.set_message(None),
+        }
+        self.set_event(GEvent {
+            event: EventType::Other,
+            state: State::None,
+        });
+        container.listener(self);
+        gpu_context.present(container, font_map)
+    }
+
+    /// 到达唤醒时刻，发送到期定时器的消息、分发长按手势并绘制动画的下一帧
+    pub fn wake_up<C: ComponentModel<M> + 'static>(
+        &mut self,
+        gpu_context: &mut GPUContext,
+        container: &mut C,
+        font_map: &mut GCharMap,
+    ) where
+        M: Clone,
+    {
+        let fired = self.fire_timers(container);
+        let gesture = self.poll_gestures(container);
+        if fired || gesture || self.animating {
+            gpu_context.present(container, font_map)
+        }
+        self.animating = false;
+    }
+
+    /// 一次事件处理结束，保存请求的截图并计算下一次唤醒时刻
+    pub fn finish<C: ComponentModel<M> + 'static>(
+        &mut self,
+        gpu_context: &mut GPUContext,
+        container: &C,
+        font_map: &mut GCharMap,
+    ) -> Option<Instant> {
+        self.save_captures(gpu_context, container, font_map);
+        self.animating |= container.is_animating();
+        self.wake = self.next_wake();
+        self.wake
+    }
+
+    /// 将到期定时器的消息依次交给容器处理，有定时器到期时返回true
+    pub fn fire_timers<C: ComponentModel<M> + 'static>(&mut self, container: &mut C) -> bool
+    where
+        M: Clone,
+    {
+        let envelopes = self.timers.take_due(Instant::now());
+        let fired = !envelopes.is_empty();
+        for envelope in envelopes {
+            self.receive(envelope);
+            self.set_event(GEvent {
+                event: EventType::Other,
+                state: State::None,
+            });
+            container.listener(self);
+        }
+        fired
+    }
+
+    /// 到达长按时长时分发长按手势，需要重绘时返回true
+    fn poll_gestures<C: ComponentModel<M> + 'static>(&mut self, container: &mut C) -> bool {
+        match self.gestures.poll(Instant::now()) {
+            Some(gesture) => {
+                self.set_event(GEvent {
+                    event: EventType::Gesture(gesture),
+                    state: State::None,
+                });
+                container.listener(self)
+            }
+            None => false,
+        }
+    }
+
+    /// 截取当前窗口帧，保存到事件处理中请求的路径
+    fn save_captures<C: ComponentModel<M> + 'static>(
+        &mut self,
+        gpu_context: &mut GPUContext,
+        container: &C,
+        font_map: &mut GCharMap,
+    ) {
+        if self.capture_requests.is_empty() {
+            return;
+        }
+        let image = gpu_context.capture(container, font_map);
+        for path in self.capture_requests.drain(..) {
+            match image.save_png(path.as_str()) {
+                Ok(()) => log::info!("save capture: {}", path),
+                Err(error) => log::error!("failed to save capture {}: {}", path, error),
+            }
+        }
+    }
+
+    /// 计算下一次唤醒时刻，取动画下一帧、最近的定时器及长按检查中最早者
+    fn next_wake(&self) -> Option<Instant> {
+        earliest(
+            earliest(
+                next_frame(self.animating, self.wake),
+                self.timers.next_deadline(),
+            ),
+            self.gestures.next_deadline(),
+        )
+    }
+}
+
+impl<M: Send + 'static, B: WindowBackend<M>> EventContext<M> for Runtime<M, B> {
+    /// 更新鼠标坐标
+    fn set_cursor_pos(&mut self, pos: Point<f32>) {
+        self.cursor_pos = pos;
+    }
+
+    fn get_cursor_pos(&self) -> Point<f32> {
+        self.cursor_pos
+    }
+
+    fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.scale_factor = scale_factor;
+    }
+
+    fn get_scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    /// 请求鼠标图标
+    fn set_cursor_icon(&mut self, cursor: Cursor) {
+        if self.cursor_request.is_none() {
+            self.cursor_request = Some(cursor);
+        }
+    }
+
+    fn claim_hover(&mut self) -> bool {
+        !std::mem::replace(&mut self.hover_claimed, true)
+    }
+
+    /// 设置输入框位置
+    fn set_ime_position(&mut self) {
+        self.backend.set_ime_position(self.cursor_pos);
+    }
+
+    fn set_event(&mut self, event: GEvent) {
+        self.window_event = Some(event);
+        self.consumed = false;
+        self.hover_claimed = false;
+    }
+
+    /// 获取当前事件
+    fn get_event(&self) -> GEvent {
+        match &self.window_event {
+            Some(event) => event.clone(),
+            None => GEvent {
+                event: EventType::Other,
+                state: State::None,
+            },
+        }
+    }
+
+    fn consume(&mut self) {
+        self.consumed = true;
+    }
+
+    fn is_consumed(&self) -> bool {
+        self.consumed
+    }
+
+    fn capture_pointer(&mut self) {
+        self.pointer_captured = true;
+    }
+
+    fn release_pointer(&mut self) {
+        self.pointer_captured = false;
+    }
+
+    fn is_pointer_captured(&self) -> bool {
+        self.pointer_captured
+    }
+
+    fn get_message(&self) -> Option<&M> {
+        self.message.as_ref()
+    }
+
+    fn set_message(&mut self, message: Option<M>) {
+        self.message = message;
+        self.message_target = None;
+    }
+
+    fn get_message_target(&self) -> Option<InstanceId> {
+        self.message_target
+    }
+
+    fn set_route(&mut self, target: Option<InstanceId>) {
+        self.route = target;
+    }
+
+    /// 发送自定义事件消息
+    fn send_message(&self, message: M) {
+        self.send(Envelope {
+            target: self.route,
+            message,
+        });
+    }
+
+    fn spawn(&mut self, command: Command<M>) {
+        let target = self.route;
+        let parts = command.into_parts();
+        self.capture_requests.extend(parts.captures);
+        for id in parts.cancelled_timers {
+            self.cancel_timer(id);
+        }
+        for timer in parts.timers {
+            self.timers.add(timer, target);
+        }
+        for task in parts.tasks {
+            let sender = self.backend.sender();
+            spawn_task(task, move |message| {
+                if let Err(error) = sender.send(Envelope { target, message }) {
+                    log::error!("failed to send command result: {}", error);
+                }
+            });
+        }
+    }
+
+    fn request_capture(&mut self, path: String) {
+        self.capture_requests.push(path);
+    }
+
+    fn subscribe(&mut self, subscription: Subscription<M>) {
+        let target = self.route;
+        let started = self.subscriptions.update(target, subscription);
+        self.start_streams(started);
+    }
+
+    fn subscribe_scope(&mut self, scope: SubscriptionScope, subscription: Subscription<M>) {
+        let target = self.route;
+        let started = self
+            .subscriptions
+            .update_scope(target, Some(scope), subscription);
+        self.start_streams(started);
+    }
+
+    fn cancel_instance_subscriptions(&mut self, id: InstanceId) {
+        self.subscriptions.remove_instance(id);
+    }
+
+    fn add_timer(&mut self, timer: Timer<M>) -> TimerId {
+        self.timers.add(timer, self.route)
+    }
+
+    fn cancel_timer(&mut self, id: TimerId) {
+        if !self.timers.cancel(id) {
+            log::warn!("timer {} is not found", id);
+        }
+    }
+
+    fn cancel_instance_timers(&mut self, id: InstanceId) {
+        self.timers.cancel_target(id);
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod testing {
+    use std::sync::mpsc::{channel, Receiver, Sender};
+
+    use super::*;
+
+    impl<M: Send + 'static> MessageSender<M> for Sender<Envelope<M>> {
+        fn send(&self, envelope: Envelope<M>) -> Result<(), String> {
+            Sender::send(self, envelope).map_err(|error| error.to_string())
+        }
+    }
+
+    /// 测试用窗口后端，消息发送到标准库通道
+    pub(crate) struct ChannelBackend<M> {
+        sender: Sender<Envelope<M>>,
+    }
+
+    impl<M: Send + 'static> WindowBackend<M> for ChannelBackend<M> {
+        type Sender = Sender<Envelope<M>>;
+
+        fn sender(&self) -> Self::Sender {
+            self.sender.clone()
+        }
+
+        fn set_cursor(&mut self, _cursor: Cursor) {}
+
+        fn set_ime_position(&mut self, _position: Point<f32>) {}
+    }
+
+    /// 创建不依赖窗口的运行时，返回其消息接收端
+    pub(crate) fn runtime<M: Send + 'static>(
+    ) -> (Runtime<M, ChannelBackend<M>>, Receiver<Envelope<M>>) {
+        let (sender, receiver) = channel();
+        (Runtime::new(ChannelBackend { sender }, 1.0), receiver)
+    }
+}
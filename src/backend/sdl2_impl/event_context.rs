@@ -1,91 +1,79 @@
+use std::cell::Cell;
 use std::fmt::Debug;
+use std::rc::Rc;
+use std::time::Instant;
 
 use futures::channel::mpsc;
 use futures::{task, Future, StreamExt};
+use sdl2::event::EventSender;
 use sdl2::event::{Event, WindowEvent};
 use sdl2::video::Window;
 use sdl2::{EventPump, EventSubsystem};
 
 use crate::adapter::{DisplayWindow, GPUContext};
+use crate::backend::runtime::{MessageSender, Runtime, WindowBackend};
+use crate::backend::sdl2_impl::event_type_transfer::{create_cursor, translate_cursor};
 use crate::event::*;
 use crate::graphic::base::*;
 use crate::instance::Setting;
 use crate::widget::*;
 
 /// 事件上下文
+pub type SEventContext<M> = Runtime<M, SdlWindow>;
+
+/// sdl2窗口后端
 #[allow(missing_debug_implementations)]
-pub struct SEventContext<M: 'static> {
-    /// 窗口id
-    window: Window,
-    /// 鼠标位置
-    cursor_pos: Point<f32>,
-    /// 窗口事件
-    window_event: Option<GEvent>,
-    /// 自定义事件
-    message: Option<M>,
+pub struct SdlWindow {
+    /// 窗口
+    pub(crate) window: Window,
+    /// 当前生效的sdl2鼠标图标，释放后图标失效，需保持存活
+    sdl_cursor: Option<sdl2::mouse::Cursor>,
     /// 自定义事件广播器
     message_channel: EventSubsystem,
 }
 
-impl<M: 'static> SEventContext<M> {
-    pub fn new(window: Window, event_channel: EventSubsystem) -> SEventContext<M> {
-        event_channel.register_custom_event::<M>().unwrap();
-        SEventContext {
-            window,
-            cursor_pos: Point::new(-1.0, -1.0),
-            window_event: None,
-            message: None,
-            message_channel: event_channel,
-        }
+impl<M: Send + 'static> MessageSender<M> for EventSender {
+    fn send(&self, envelope: Envelope<M>) -> Result<(), String> {
+        self.push_custom_event(envelope)
     }
 }
 
-impl<M> EventContext<M> for SEventContext<M> {
-    /// 更新鼠标坐标
-    fn set_cursor_pos(&mut self, pos: Point<f32>) {
-        self.cursor_pos = pos;
-    }
-
-    fn get_cursor_pos(&self) -> Point<f32> {
-        self.cursor_pos
-    }
-
-    /// 设置鼠标图标
-    fn set_cursor_icon(&mut self, _cursor: Cursor) {}
-    /// 设置输入框位置
-    fn set_ime_position(&mut self) {}
+impl<M: Send + 'static> WindowBackend<M> for SdlWindow {
+    type Sender = EventSender;
 
-    fn set_event(&mut self, event: GEvent) {
-        self.window_event = Some(event)
+    fn sender(&self) -> Self::Sender {
+        self.message_channel.event_sender()
     }
 
-    /// 获取当前事件
-    fn get_event(&self) -> GEvent {
-        return if let Some(event) = self.window_event.clone() {
-            event
-        } else {
-            GEvent {
-                event: EventType::Other,
-                state: State::None,
-            }
+    fn set_cursor(&mut self, cursor: Cursor) {
+        let sdl_cursor = match &cursor {
+            Cursor::Custom(custom) => create_cursor(custom),
+            cursor => sdl2::mouse::Cursor::from_system(translate_cursor(cursor)),
         };
+        match sdl_cursor {
+            Ok(sdl_cursor) => {
+                sdl_cursor.set();
+                self.sdl_cursor = Some(sdl_cursor);
+            }
+            Err(error) => log::warn!("failed to set cursor {:?}: {}", cursor, error),
+        }
     }
 
-    fn get_message(&self) -> Option<&M> {
-        self.message.as_ref()
-    }
+    fn set_ime_position(&mut self, _position: Point<f32>) {}
+}
 
-    fn set_message(&mut self, message: Option<M>) {
-        self.message = message;
-    }
-    /// 发送自定义事件消息
-    fn send_message(&self, message: M) {
-        self.message_channel.push_custom_event(message).unwrap();
+/// 计算窗口缩放比例，即可绘制区域的像素尺寸与窗口逻辑尺寸之比
+fn scale_factor(window: &Window) -> f32 {
+    let (width, _) = window.size();
+    let (drawable_width, _) = window.drawable_size();
+    if width == 0 {
+        return 1.0;
     }
+    drawable_width as f32 / width as f32
 }
 
 /// 初始化窗口
-pub(crate) async fn init<M: 'static + Debug>(setting: Setting) -> DisplayWindow<M> {
+pub(crate) async fn init<M: 'static + Send + Debug>(setting: Setting) -> DisplayWindow<M> {
     log::info!("Initializing the window...");
     let sdl_context = sdl2::init().unwrap();
     let video_subsystem = sdl_context.video().unwrap();
@@ -95,51 +83,81 @@ pub(crate) async fn init<M: 'static + Debug>(setting: Setting) -> DisplayWindow<
         .window(setting.title.as_str(), window_size.x, window_size.y)
         .position_centered()
         .resizable()
+        .allow_highdpi()
         .build()
         .map_err(|e| e.to_string())
         .unwrap();
     let channel = sdl_context.event().unwrap();
     let event_pump = sdl_context.event_pump().unwrap();
-    let gpu_context = GPUContext::new(&window, window_size).await;
-    let event_context: SEventContext<M> = SEventContext::new(window, channel);
-    let font_map = GCharMap::new(setting.font_path, DEFAULT_FONT_SIZE);
-    let display_window = DisplayWindow {
+    let (drawable_width, drawable_height) = window.drawable_size();
+    let drawable_size = Point::new(drawable_width, drawable_height);
+    let mut gpu_context = GPUContext::new(&window, drawable_size, setting.sample_count).await;
+    channel.register_custom_event::<Envelope<M>>().unwrap();
+    let scale_factor = scale_factor(&window);
+    let event_context: SEventContext<M> = Runtime::new(
+        SdlWindow {
+            window,
+            sdl_cursor: None,
+            message_channel: channel,
+        },
+        scale_factor,
+    );
+    let mut font_map = GCharMap::new(setting.font_path, DEFAULT_FONT_SIZE);
+    gpu_context.set_scale_factor(scale_factor);
+    font_map.set_scale_factor(scale_factor);
+    DisplayWindow {
         gpu_context,
         event_loop: event_pump,
         event_context,
         font_map,
-    };
-    return display_window;
+    }
 }
 
 /// 运行窗口实例
+/// 定时器不使用sdl2的定时器子系统：其回调在独立线程中执行，只能推送事件回主线程，
+/// 且无法与动画帧、长按检查合并；这里与winit后端共用运行时的定时器队列，
+/// 以`wait_event_timeout`等待到最近的唤醒时刻
 pub(crate) fn run<C, M>(window: DisplayWindow<M>, container: C)
 where
     C: ComponentModel<M> + 'static,
-    M: 'static + Debug,
+    M: 'static + Clone + Send + Debug,
 {
     let (sender, receiver) = mpsc::unbounded();
+    // 监听器设置的下一次唤醒时刻，用于驱动动画及定时器
+    let wake = Rc::new(Cell::new(None));
     let mut instance_listener = Box::pin(event_listener(
         window.gpu_context,
         window.event_context,
         window.font_map,
         container,
         receiver,
+        wake.clone(),
     ));
     let mut context = task::Context::from_waker(task::noop_waker_ref());
     let mut event_pump: EventPump = window.event_loop;
     loop {
-        for event in event_pump.poll_iter() {
-            sender.unbounded_send(event).unwrap();
-            let poll = instance_listener.as_mut().poll(&mut context);
-            match poll {
-                task::Poll::Pending => {
-                    // println!("--------pending--------");
-                }
-                task::Poll::Ready(_) => {
-                    // println!("--------ready--------");
-                }
-            };
+        // 有唤醒时刻时限时等待事件，超时则发送空事件通知监听器到达唤醒时刻
+        let event = match wake.get() {
+            Some(instant) => {
+                let timeout = instant.saturating_duration_since(Instant::now());
+                event_pump.wait_event_timeout(timeout.as_micros().div_ceil(1000) as u32)
+            }
+            None => Some(event_pump.wait_event()),
+        };
+        sender.unbounded_send(event).unwrap();
+        let poll = instance_listener.as_mut().poll(&mut context);
+        match poll {
+            task::Poll::Pending => {
+                // println!("--------pending--------");
+            }
+            task::Poll::Ready(_) => {
+                // println!("--------ready--------");
+            }
+        };
+        // 事件持续到达时不会等待超时，已到唤醒时刻则补发空事件，避免定时器及动画被推迟
+        if wake.get().is_some_and(|instant| instant <= Instant::now()) {
+            sender.unbounded_send(None).unwrap();
+            let _ = instance_listener.as_mut().poll(&mut context);
         }
     }
 }
@@ -150,48 +168,76 @@ async fn event_listener<C, M>(
     mut event_context: SEventContext<M>,
     mut font_map: GCharMap,
     mut container: C,
-    mut receiver: mpsc::UnboundedReceiver<sdl2::event::Event>,
+    mut receiver: mpsc::UnboundedReceiver<Option<sdl2::event::Event>>,
+    wake: Rc<Cell<Option<Instant>>>,
 ) where
     C: ComponentModel<M> + 'static,
-    M: 'static + Debug,
+    M: 'static + Clone + Send + Debug,
 {
+    event_context.start(&mut container);
+    wake.set(event_context.wake());
     while let Some(event) = receiver.next().await {
-        if event.is_user_event() {
-            event_context.set_message(event.as_user_event_type::<M>());
-            log::debug!("customer event: {:?}", event_context.get_message());
-        }
-        if event.get_window_id() == Some(event_context.window.id()) {
-            match event {
-                Event::Window { win_event, .. } => match win_event {
-                    WindowEvent::Resized(width, height)
-                    | WindowEvent::SizeChanged(width, height) => {
-                        let new_size = Point::new(width as u32, height as u32);
-                        gpu_context.update_surface_configure(new_size);
-                    }
-                    WindowEvent::Close => {
+        match event {
+            // 空事件表示到达唤醒时刻，发送到期定时器的消息并绘制动画的下一帧
+            None => event_context.wake_up(&mut gpu_context, &mut container, &mut font_map),
+            Some(event) if event.is_user_event() => {
+                let envelope = event.as_user_event_type::<Envelope<M>>();
+                log::debug!("customer event: {:?}", envelope);
+                event_context.handle_message(
+                    &mut gpu_context,
+                    &mut container,
+                    &mut font_map,
+                    envelope,
+                );
+            }
+            Some(event) if event.get_window_id() == Some(event_context.backend.window.id()) => {
+                match event {
+                    Event::Window { win_event, .. } => match win_event {
+                        WindowEvent::Resized(..) | WindowEvent::SizeChanged(..) => {
+                            // 事件中的尺寸为逻辑尺寸，交换缓冲区使用物理像素尺寸
+                            let window = &event_context.backend.window;
+                            let scale_factor = scale_factor(window);
+                            let (width, height) = window.drawable_size();
+                            event_context.set_scale_factor(scale_factor);
+                            gpu_context.set_scale_factor(scale_factor);
+                            font_map.set_scale_factor(scale_factor);
+                            gpu_context.update_surface_configure(Point::new(width, height));
+                        }
+                        WindowEvent::Close => {
+                            println!("----- Close window -----");
+                            ::std::process::exit(0);
+                        }
+                        _ => gpu_context.present(&mut container, &mut font_map),
+                    },
+                    Event::Quit { .. } => {
                         println!("----- Close window -----");
                         ::std::process::exit(0);
                     }
-                    _ => gpu_context.present(&mut container, &mut font_map),
-                },
-                Event::Quit { .. } => {
-                    println!("----- Close window -----");
-                    ::std::process::exit(0);
-                }
-                Event::MouseMotion { x, y, .. } => {
-                    event_context.set_cursor_pos(Point::new(x as f32, y as f32))
-                }
-                Event::MouseButtonDown { .. }
-                | Event::MouseButtonUp { .. }
-                | Event::KeyUp { .. }
-                | Event::KeyDown { .. } => {
-                    event_context.set_event(event.into());
-                    if container.listener(&mut event_context) {
-                        gpu_context.present(&mut container, &mut font_map)
+                    Event::MouseMotion { x, y, .. } => {
+                        event_context.set_cursor_pos(Point::new(x as f32, y as f32));
+                        event_context.handle_event(
+                            &mut gpu_context,
+                            &mut container,
+                            &mut font_map,
+                            event.into(),
+                        );
+                    }
+                    Event::MouseButtonDown { .. }
+                    | Event::MouseButtonUp { .. }
+                    | Event::KeyUp { .. }
+                    | Event::KeyDown { .. } => {
+                        event_context.handle_event(
+                            &mut gpu_context,
+                            &mut container,
+                            &mut font_map,
+                            event.into(),
+                        );
                     }
+                    _ => {}
                 }
-                _ => {}
             }
+            Some(_) => {}
         }
+        wake.set(event_context.finish(&mut gpu_context, &container, &mut font_map));
     }
 }
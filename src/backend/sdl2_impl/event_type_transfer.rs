@@ -1,6 +1,8 @@
 use sdl2::event::Event;
 use sdl2::keyboard::Keycode;
-use sdl2::mouse::MouseButton;
+use sdl2::mouse::{MouseButton, SystemCursor};
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::surface::Surface;
 
 use crate::event::*;
 
@@ -50,6 +52,10 @@ impl From<sdl2::event::Event> for GEvent {
                 event: EventType::Mouse(mouse_btn.into()),
                 state: State::Released,
             },
+            Event::MouseMotion { .. } => GEvent {
+                event: EventType::CursorMoved,
+                state: State::None,
+            },
             _ => GEvent {
                 event: EventType::Other,
                 state: State::None,
@@ -58,6 +64,41 @@ impl From<sdl2::event::Event> for GEvent {
     }
 }
 
+/// 鼠标图标转换，自定义图标由create_cursor创建，此处返回默认图标
+pub(crate) fn translate_cursor(cursor: &Cursor) -> SystemCursor {
+    match cursor {
+        Cursor::Default => SystemCursor::Arrow,
+        Cursor::Text => SystemCursor::IBeam,
+        Cursor::Pointer => SystemCursor::Hand,
+        Cursor::Move => SystemCursor::SizeAll,
+        Cursor::Crosshair => SystemCursor::Crosshair,
+        Cursor::Wait => SystemCursor::Wait,
+        Cursor::NotAllowed => SystemCursor::No,
+        Cursor::ResizeHorizontal => SystemCursor::SizeWE,
+        Cursor::ResizeVertical => SystemCursor::SizeNS,
+        Cursor::ResizeNeSw => SystemCursor::SizeNESW,
+        Cursor::ResizeNwSe => SystemCursor::SizeNWSE,
+        Cursor::Custom(_) => SystemCursor::Arrow,
+    }
+}
+
+/// 由RGBA图像创建sdl2鼠标图标，sdl2会复制图像数据
+pub(crate) fn create_cursor(cursor: &CustomCursor) -> Result<sdl2::mouse::Cursor, String> {
+    let image = cursor
+        .image()
+        .ok_or_else(|| format!("unregistered custom cursor {:?}", cursor))?;
+    let hotspot = cursor.hotspot();
+    let mut data = image.data.clone();
+    let surface = Surface::from_data(
+        &mut data,
+        image.width,
+        image.height,
+        image.width * 4,
+        PixelFormatEnum::RGBA32,
+    )?;
+    sdl2::mouse::Cursor::from_surface(surface, hotspot.x as i32, hotspot.y as i32)
+}
+
 /// 键盘按键类型转换
 pub(crate) fn translate_key(key: Keycode) -> KeyCode {
     match key {
@@ -1,7 +1,15 @@
+/// 按顺序拼接shader_c目录下的着色器源码，公共函数放在前面
+macro_rules! shader_source {
+    ($($file:literal),+) => {
+        concat!($(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/shader_c/", $file))),+)
+    };
+}
+
 pub use pipeline_state::*;
 pub use render_utils::*;
 pub use shape_transfer::*;
 pub use shape_vertex_layout::*;
+pub use tessellation::*;
 pub use texture::*;
 pub use texture_vertex_layout::*;
 pub use vertex_buffer::*;
@@ -16,6 +24,8 @@ mod render_utils;
 mod shape_transfer;
 /// 定义图形顶点缓冲布局
 mod shape_vertex_layout;
+/// 路径细分，将曲线和描边转换为三角形
+mod tessellation;
 /// 定义纹理
 mod texture;
 /// 定义纹理缓冲布局
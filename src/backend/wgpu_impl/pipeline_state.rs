@@ -9,6 +9,17 @@ use crate::backend::wgpu_impl::*;
 pub struct PipelineState {
     /// 渲染管道容器，不同图形设置不同的管道
     context: HashMap<ShapeType, RenderPipeline>,
+    /// 多重采样数
+    sample_count: u32,
+    /// 全局参数绑定组布局，所有变换共用
+    globals_layout: BindGroupLayout,
+}
+
+/// 渲染管道错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum PipelineError {
+    /// 该图形类型的管道已注册
+    Duplicate(ShapeType),
 }
 
 /// 图元渲染器
@@ -21,32 +32,68 @@ pub struct Shader {
 }
 
 impl PipelineState {
-    pub fn default(device: &Device) -> Self {
+    pub fn default(device: &Device, sample_count: u32) -> Self {
         // 固定渲染管道配置：纹理管道，矩形管道，线框管道等。
         // 全局设置
         log::info!("create the PipelineState obj");
-        let context = HashMap::with_capacity(4);
-        let mut glob_pipeline = Self { context };
-        glob_pipeline.set_pipeline::<RectVertex>(device);
-        glob_pipeline.set_pipeline::<CircleVertex>(device);
-        glob_pipeline.set_pipeline::<PointVertex>(device);
-        glob_pipeline.set_pipeline::<TextureVertex>(device);
+        let context = HashMap::with_capacity(6);
+        let mut glob_pipeline = Self {
+            context,
+            sample_count,
+            globals_layout: device.create_bind_group_layout(GLOBALS_BIND_GROUP_LAYOUT),
+        };
+        glob_pipeline
+            .set_pipeline::<RectVertex>(device)
+            .expect("built-in pipeline registered twice");
+        glob_pipeline
+            .set_pipeline::<CircleVertex>(device)
+            .expect("built-in pipeline registered twice");
+        glob_pipeline
+            .set_pipeline::<PointVertex>(device)
+            .expect("built-in pipeline registered twice");
+        glob_pipeline
+            .set_pipeline::<TextureVertex>(device)
+            .expect("built-in pipeline registered twice");
+        glob_pipeline
+            .set_pipeline::<ShadowVertex>(device)
+            .expect("built-in pipeline registered twice");
+        glob_pipeline
+            .set_pipeline::<BlurVertex>(device)
+            .expect("built-in pipeline registered twice");
         glob_pipeline
     }
-    /// 创建渲染管道
-    pub fn set_pipeline<V>(&mut self, device: &Device)
+    /// 创建渲染管道，图形类型已注册时返回错误且不创建管道
+    pub fn set_pipeline<V>(&mut self, device: &Device) -> Result<(), PipelineError>
     where
         V: VertexLayout,
     {
-        // 作用：绑定着色器，图形填充
-        let render_pipeline = V::create_render_pipeline(device);
         let shape_type = V::get_shape_type();
-        if self.context.get(&shape_type).is_none() {
-            self.context.insert(shape_type, render_pipeline);
+        if self.context.contains_key(&shape_type) {
+            return Err(PipelineError::Duplicate(shape_type));
         }
+        // 作用：绑定着色器，图形填充
+        let render_pipeline = V::create_render_pipeline(device, self.sample_count);
+        self.context.insert(shape_type, render_pipeline);
+        Ok(())
+    }
+    /// 获取全局参数绑定组布局
+    pub fn globals_layout(&self) -> &BindGroupLayout {
+        &self.globals_layout
     }
     /// 获取渲染管线
     pub fn get_pipeline(&self, shape_type: ShapeType) -> Option<&RenderPipeline> {
         self.context.get(&shape_type)
     }
 }
+
+impl std::fmt::Display for PipelineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PipelineError::Duplicate(shape_type) => {
+                write!(f, "pipeline for {:?} is already registered", shape_type)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PipelineError {}
@@ -1,9 +1,154 @@
-use wgpu::{CommandEncoder, SurfaceTexture, TextureView};
+use std::num::NonZeroU64;
+use std::rc::Rc;
+
+use wgpu::{BindGroup, BindGroupLayout, CommandEncoder, SurfaceTexture, TextureView};
 
 use crate::backend::wgpu_impl::*;
 use crate::graphic::base::*;
 use crate::graphic::render_api::PaintBrush;
-use crate::graphic::style::Style;
+use crate::graphic::style::{BoxShadow, Style};
+
+/// 全局参数缓冲的初始槽位数
+const GLOBALS_POOL_CAPACITY: u64 = 16;
+
+/// 着色器全局参数，对应globals.wgsl
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub(crate) struct Globals {
+    transform: [[f32; 4]; 4],
+    inverse: [[f32; 4]; 4],
+    viewport: [f32; 4],
+}
+
+/// 变换栈元素
+#[derive(Debug)]
+struct TransformState {
+    /// 与所有父级变换组合后的变换
+    transform: Transform,
+    /// 变换不可逆（如缩放为0）时不绘制
+    visible: bool,
+    /// 全局参数绑定组
+    uniform: Rc<BindGroup>,
+    /// 全局参数在缓冲中的动态偏移
+    offset: u32,
+}
+
+/// 全局参数缓冲池
+/// 各变换的全局参数写入同一uniform缓冲的不同槽位，以动态偏移绑定，
+/// 每次渲染开始时复用全部槽位，槽位不足时按两倍容量重建缓冲
+#[derive(Debug)]
+pub(crate) struct GlobalsPool {
+    buffer: wgpu::Buffer,
+    bind_group: Rc<BindGroup>,
+    /// 槽位间隔，按设备的uniform偏移对齐要求取整
+    stride: u64,
+    capacity: u64,
+    /// 本次渲染已使用的槽位数
+    used: u64,
+}
+
+impl GlobalsPool {
+    pub(crate) fn new(device: &wgpu::Device, layout: &BindGroupLayout) -> Self {
+        let alignment = device.limits().min_uniform_buffer_offset_alignment as u64;
+        let size = std::mem::size_of::<Globals>() as u64;
+        let stride = size.div_ceil(alignment) * alignment;
+        Self::with_capacity(device, layout, stride, GLOBALS_POOL_CAPACITY)
+    }
+
+    fn with_capacity(
+        device: &wgpu::Device,
+        layout: &BindGroupLayout,
+        stride: u64,
+        capacity: u64,
+    ) -> Self {
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("globals"),
+            size: stride * capacity,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("globals"),
+            layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                    buffer: &buffer,
+                    offset: 0,
+                    size: NonZeroU64::new(std::mem::size_of::<Globals>() as u64),
+                }),
+            }],
+        });
+        GlobalsPool {
+            buffer,
+            bind_group: Rc::new(bind_group),
+            stride,
+            capacity,
+            used: 0,
+        }
+    }
+
+    /// 开始新的一次渲染，复用全部槽位
+    pub(crate) fn reset(&mut self) {
+        self.used = 0;
+    }
+
+    /// 写入全局参数，返回绑定组及动态偏移
+    /// 已录制的渲染命令仍引用旧缓冲，扩容不影响本次渲染中已写入的参数
+    pub(crate) fn push(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        layout: &BindGroupLayout,
+        globals: &Globals,
+    ) -> (Rc<BindGroup>, u32) {
+        if self.used == self.capacity {
+            *self = Self::with_capacity(device, layout, self.stride, self.capacity * 2);
+        }
+        let offset = self.used * self.stride;
+        queue.write_buffer(&self.buffer, offset, bytemuck::cast_slice(&[*globals]));
+        self.used += 1;
+        (self.bind_group.clone(), offset as u32)
+    }
+}
+
+/// 帧缓冲像素坐标下的裁剪区域
+#[derive(Copy, Clone, Debug)]
+struct Scissor {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+impl Scissor {
+    /// 求两个裁剪区域的交集
+    fn intersect(&self, other: &Scissor) -> Scissor {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = (self.x + self.width).min(other.x + other.width);
+        let bottom = (self.y + self.height).min(other.y + other.height);
+        Scissor {
+            x,
+            y,
+            width: right.saturating_sub(x),
+            height: bottom.saturating_sub(y),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+}
+
+/// 离屏图层，弹出后放回图形渲染上下文的图层池中复用
+#[derive(Debug)]
+pub(crate) struct Layer {
+    target: RenderTarget,
+    /// 多重采样渲染目标，采样数为1时不创建
+    msaa_view: Option<TextureView>,
+    opacity: f32,
+}
 
 /// 渲染工具封装结构体
 /// 基于wgpu实现渲染API定义的基本渲染方法
@@ -11,19 +156,26 @@ use crate::graphic::style::Style;
 pub struct RenderUtil<'a> {
     /// wgpu提供的gpu命令编码器，用于发送渲染命令
     pub encoder: CommandEncoder,
-    /// 目标渲染区域
+    /// 目标渲染区域，即离屏帧缓冲
     pub view: TextureView,
     /// 图形渲染上下文
     pub context: &'a mut WGPUContext,
     /// 纹理配置上下文
     pub g_texture: GTexture,
+    /// 变换栈，栈底为单位变换，其上为逻辑坐标到物理像素的缩放
+    transforms: Vec<TransformState>,
+    /// 图层栈，为空时绘制到离屏帧缓冲
+    layers: Vec<Layer>,
+    /// 裁剪区域栈，为空时不裁剪
+    clips: Vec<Scissor>,
 }
 
 impl<'a> RenderUtil<'a> {
-    /// 创建渲染工具
-    /// 参数：目标渲染区域，图形渲染上下文
-    pub fn new(target_view: &SurfaceTexture, gpu_context: &'a mut WGPUContext) -> Self {
-        let view = target_view
+    /// 创建渲染工具，绘制到图形渲染上下文的离屏帧缓冲
+    /// 参数：图形渲染上下文
+    pub fn new(gpu_context: &'a mut WGPUContext) -> Self {
+        let view = gpu_context
+            .frame
             .texture
             .create_view(&wgpu::TextureViewDescriptor::default());
         let encoder = gpu_context
@@ -37,21 +189,152 @@ impl<'a> RenderUtil<'a> {
             Point::new(40, 40),
             wgpu::TextureFormat::R8Unorm,
         );
+        gpu_context.globals.reset();
+        let identity = TransformState::new(gpu_context, Transform::identity());
+        let scale_factor = gpu_context.get_scale_factor();
+        let root = TransformState::new(gpu_context, Transform::scaling(scale_factor, scale_factor));
         RenderUtil {
             encoder,
             view,
             context: gpu_context,
             g_texture,
+            transforms: vec![identity, root],
+            layers: Vec::new(),
+            clips: Vec::new(),
+        }
+    }
+
+    /// 以当前变换将顶点缓冲绘制到当前图层
+    pub fn draw_buffer(&mut self, buffer: &VertexBuffer, texture: Option<&TextureBufferData>) {
+        if !self.transforms.last().unwrap().visible {
+            return;
         }
+        self.render_buffer(buffer, texture, self.transforms.len() - 1);
+    }
+
+    /// 以变换栈中指定位置的变换绘制顶点缓冲
+    fn render_buffer(
+        &mut self,
+        buffer: &VertexBuffer,
+        texture: Option<&TextureBufferData>,
+        transform_index: usize,
+    ) {
+        if buffer.num_indices == 0 {
+            return;
+        }
+        let scissor = self.clips.last().copied();
+        if scissor.is_some_and(|scissor| scissor.is_empty()) {
+            return;
+        }
+        // 获取顶点缓冲对应的渲染管道
+        let pipeline = match self.context.get_pipeline(buffer.shape_type) {
+            Some(pipeline) => pipeline,
+            None => {
+                log::error!("no pipeline registered for {:?}", buffer.shape_type);
+                return;
+            }
+        };
+        let (view, msaa_view) = match self.layers.last() {
+            Some(layer) => (&layer.target.view, layer.msaa_view.as_ref()),
+            None => (&self.view, self.context.msaa_view.as_ref()),
+        };
+        // 创建临时渲染变量，并设置渲染管道
+        let mut render_pass = vertex_buffer::create_render_pass(
+            &mut self.encoder,
+            view,
+            msaa_view,
+            RenderModel::Load,
+        );
+        render_pass.set_pipeline(pipeline);
+        if let Some(scissor) = scissor {
+            render_pass.set_scissor_rect(scissor.x, scissor.y, scissor.width, scissor.height);
+        }
+        let globals = &self.transforms[transform_index];
+        render_pass.set_bind_group(0, &globals.uniform, &[globals.offset]);
+        // 绑定纹理缓冲
+        if let Some(texture_buffer) = texture {
+            render_pass.set_bind_group(1, &texture_buffer.uniform, &[]);
+        }
+        // 设置顶点缓冲及其索引缓冲，并调用渲染方法
+        render_pass.set_vertex_buffer(0, buffer.vertex_buffer.slice(..));
+        render_pass.set_index_buffer(buffer.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+        render_pass.draw_indexed(0..buffer.num_indices, 0, 0..1);
+    }
+
+    /// 将离屏帧缓冲绘制到交换缓冲区
+    pub fn present_frame(&mut self, target: &SurfaceTexture) {
+        let target_view = target
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        let size = self.context.get_surface_size();
+        let frame_rect = Rectangle::new(0.0, 0.0, size.x, size.y);
+        let frame_vertex = TextureVertex::new_image(self.context, &frame_rect, WHITE);
+        let mut render_pass = vertex_buffer::create_render_pass(
+            &mut self.encoder,
+            &target_view,
+            None,
+            RenderModel::Clear(BACKGROUND_COLOR),
+        );
+        render_pass.set_pipeline(&self.context.blit_pipeline);
+        let globals = &self.transforms[0];
+        render_pass.set_bind_group(0, &globals.uniform, &[globals.offset]);
+        render_pass.set_bind_group(1, &self.context.frame.buffer.uniform, &[]);
+        render_pass.set_vertex_buffer(0, frame_vertex.vertex_buffer.slice(..));
+        render_pass.set_index_buffer(
+            frame_vertex.index_buffer.slice(..),
+            wgpu::IndexFormat::Uint16,
+        );
+        render_pass.draw_indexed(0..frame_vertex.num_indices, 0, 0..1);
+    }
+
+    /// 绘制矩形阴影
+    fn draw_shadow(&mut self, rect: &Rectangle, style: &Style, shadow: &BoxShadow) {
+        let shadow_buffer = ShadowVertex::new(self.context, rect, style, shadow);
+        shadow_buffer.render(self, None);
+    }
+
+    /// 模糊矩形区域内已绘制的内容
+    /// 先将当前图层复制一份，再以复制的内容作为纹理绘制模糊效果
+    fn draw_backdrop(&mut self, rect: &Rectangle, style: &Style) {
+        let size = self.context.get_surface_size();
+        let backdrop = match self.context.backdrop.take() {
+            Some(backdrop) if backdrop.size == size => backdrop,
+            _ => RenderTarget::new(&self.context.device, size, self.context.frame_format()),
+        };
+        let source = match self.layers.last() {
+            Some(layer) => &layer.target,
+            None => &self.context.frame,
+        };
+        backdrop.copy_from(&mut self.encoder, source);
+        let blur_buffer = BlurVertex::new(self.context, rect, style);
+        blur_buffer.render(self, Some(&backdrop.buffer));
+        self.context.backdrop = Some(backdrop);
     }
 }
 
 impl PaintBrush for RenderUtil<'_> {
     fn clear_frame(&mut self, color: RGBA) {
-        vertex_buffer::create_render_pass(&mut self.encoder, &self.view, RenderModel::Clear(color));
+        let (view, msaa_view) = match self.layers.last() {
+            Some(layer) => (&layer.target.view, layer.msaa_view.as_ref()),
+            None => (&self.view, self.context.msaa_view.as_ref()),
+        };
+        vertex_buffer::create_render_pass(
+            &mut self.encoder,
+            view,
+            msaa_view,
+            RenderModel::Clear(color),
+        );
     }
 
     fn draw_shape(&mut self, shape: &Box<dyn ShapeGraph>, shape_style: Style) {
+        if let Some(rect) = shape.get_rect() {
+            if let Some(shadow) = shape_style.get_shadow() {
+                self.draw_shadow(&rect, &shape_style, shadow);
+            }
+            if shape_style.get_backdrop_blur() > 0.0 {
+                self.draw_backdrop(&rect, &shape_style);
+            }
+        }
         let shape_buffer = shape.to_buffer(self.context, shape_style);
         shape_buffer.render(self, None);
     }
@@ -64,8 +347,10 @@ impl PaintBrush for RenderUtil<'_> {
         text_color: RGBA,
     ) {
         let mut x = text_rect.position.x + 8.;
-        let scale = 10. / (font_map.scale / 2.5);
+        // 字形按物理像素光栅化，换算回逻辑尺寸
+        let scale_factor = font_map.scale_factor;
         for c in text.chars() {
+            let scale_width = font_map.char_width(c);
             let c_font = font_map.character_texture(
                 c,
                 &mut self.g_texture,
@@ -75,20 +360,147 @@ impl PaintBrush for RenderUtil<'_> {
             let c_buffer = c_font.texture.as_ref().unwrap();
             let c_x = x;
             let c_y = text_rect.position.y;
-            let scale_width = c_buffer.width as f32 * scale;
-            let c_rect = Rectangle::new(c_x, c_y, scale_width as u32, c_buffer.height);
-            x = x + scale_width;
-            let c_vertex = TextureVertex::new(&self.context, &c_rect, text_color);
+            let scale_height = c_buffer.height as f32 / scale_factor;
+            let c_rect = Rectangle::new(c_x, c_y, scale_width as u32, scale_height as u32);
+            x += scale_width;
+            let c_vertex = TextureVertex::new(self.context, &c_rect, text_color);
 
-            c_vertex.render(self, Some(&c_buffer));
+            c_vertex.render(self, Some(c_buffer));
         }
     }
 
-    fn draw_image(&mut self, image_rect: &Rectangle, image: ImageRaw) {
+    fn draw_image(&mut self, image_rect: &Rectangle, image: &ImageSource) {
+        let context = &mut *self.context;
+        // 按物理像素尺寸栅格化，高分屏上图标保持清晰
+        let scale_factor = context.get_scale_factor();
+        let size = Point::new(
+            (image_rect.width as f32 * scale_factor).round() as u32,
+            (image_rect.height as f32 * scale_factor).round() as u32,
+        );
         let image_buffer =
-            self.g_texture
-                .create_bind_group(&self.context.device, &self.context.queue, image);
-        let image_vertex = TextureVertex::new(&self.context, &image_rect, ALPHA);
-        image_vertex.render(self, Some(&image_buffer))
+            context
+                .image_cache
+                .get_or_upload(&context.device, &context.queue, image, size);
+        let image_vertex = TextureVertex::new_image(self.context, image_rect, WHITE);
+        image_vertex.render(self, Some(image_buffer.as_ref()))
+    }
+
+    fn push_transform(&mut self, transform: Transform) {
+        let parent = self.transforms.last().unwrap().transform;
+        let state = TransformState::new(self.context, parent.then(&transform));
+        self.transforms.push(state);
+    }
+
+    fn pop_transform(&mut self) {
+        if self.transforms.len() > 2 {
+            self.transforms.pop();
+        } else {
+            log::warn!("pop_transform called without a matching push_transform");
+        }
+    }
+
+    fn push_layer(&mut self, opacity: f32) {
+        let size = self.context.get_surface_size();
+        let pool = &mut self.context.layer_pool;
+        let mut layer = match pool.iter().position(|layer| layer.target.size == size) {
+            Some(index) => pool.swap_remove(index),
+            None => {
+                let format = self.context.frame_format();
+                let sample_count = self.context.get_sample_count();
+                let msaa_view = if sample_count > 1 {
+                    Some(create_multisampled_view(
+                        &self.context.device,
+                        size,
+                        format,
+                        sample_count,
+                    ))
+                } else {
+                    None
+                };
+                Layer {
+                    target: RenderTarget::new(&self.context.device, size, format),
+                    msaa_view,
+                    opacity: 1.0,
+                }
+            }
+        };
+        layer.opacity = opacity.clamp(0.0, 1.0);
+        self.layers.push(layer);
+        self.clear_frame(RGBA(0.0, 0.0, 0.0, 0.0));
+    }
+
+    fn push_clip(&mut self, clip_rect: Rectangle) {
+        let transform = self.transforms.last().unwrap().transform;
+        let size = self.context.get_surface_size();
+        let (x, y) = (clip_rect.position.x, clip_rect.position.y);
+        let (w, h) = (clip_rect.width as f32, clip_rect.height as f32);
+        let corners = [
+            Point::new(x, y),
+            Point::new(x + w, y),
+            Point::new(x, y + h),
+            Point::new(x + w, y + h),
+        ]
+        .map(|corner| transform.apply(corner));
+        // 取变换后的外接矩形，并限制在帧缓冲范围内
+        let left = corners.iter().map(|p| p.x).fold(f32::MAX, f32::min);
+        let top = corners.iter().map(|p| p.y).fold(f32::MAX, f32::min);
+        let right = corners.iter().map(|p| p.x).fold(f32::MIN, f32::max);
+        let bottom = corners.iter().map(|p| p.y).fold(f32::MIN, f32::max);
+        let left = left.floor().clamp(0.0, size.x as f32) as u32;
+        let top = top.floor().clamp(0.0, size.y as f32) as u32;
+        let right = right.ceil().clamp(0.0, size.x as f32) as u32;
+        let bottom = bottom.ceil().clamp(0.0, size.y as f32) as u32;
+        let mut scissor = Scissor {
+            x: left,
+            y: top,
+            width: right.saturating_sub(left),
+            height: bottom.saturating_sub(top),
+        };
+        if let Some(parent) = self.clips.last() {
+            scissor = scissor.intersect(parent);
+        }
+        self.clips.push(scissor);
+    }
+
+    fn pop_clip(&mut self) {
+        if self.clips.pop().is_none() {
+            log::warn!("pop_clip called without a matching push_clip");
+        }
+    }
+
+    fn pop_layer(&mut self) {
+        let layer = match self.layers.pop() {
+            Some(layer) => layer,
+            None => {
+                log::warn!("pop_layer called without a matching push_layer");
+                return;
+            }
+        };
+        // 图层内容已经过变换，合成时使用栈底的单位变换
+        let size = layer.target.size;
+        let layer_rect = Rectangle::new(0.0, 0.0, size.x, size.y);
+        let layer_vertex = TextureVertex::new_layer(self.context, &layer_rect, layer.opacity);
+        self.render_buffer(&layer_vertex, Some(&layer.target.buffer), 0);
+        // 合成命令按录制顺序执行，图层可在本帧内立即复用
+        self.context.layer_pool.push(layer);
+    }
+}
+
+impl TransformState {
+    fn new(gpu_context: &mut WGPUContext, transform: Transform) -> Self {
+        let inverse = transform.inverse();
+        let size = gpu_context.get_surface_size();
+        let globals = Globals {
+            transform: transform.to_mat4(),
+            inverse: inverse.unwrap_or_default().to_mat4(),
+            viewport: [size.x as f32, size.y as f32, 0.0, 0.0],
+        };
+        let (uniform, offset) = gpu_context.push_globals(&globals);
+        TransformState {
+            transform,
+            visible: inverse.is_some(),
+            uniform,
+            offset,
+        }
     }
 }
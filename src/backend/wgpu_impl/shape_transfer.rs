@@ -16,46 +16,82 @@ pub enum ShapeType {
     POINT,
     /// 圆
     Circle,
+    /// 阴影
+    SHADOW,
+    /// 背景模糊
+    BLUR,
+    /// 用户自定义图形，名称需唯一，对应的管道通过`WGPUContext::register_pipeline`注册
+    Custom(&'static str),
 }
 
 impl ShapeGraph for Rectangle {
     fn to_buffer(&self, gpu_context: &GPUContext, style: Style) -> VertexBuffer {
-        let rect_vertex = RectVertex::new(&self, style);
-        let rect_vertex = VertexBuffer::create_vertex_buf::<RectVertex>(
+        let rect_vertex = RectVertex::new(self, style);
+        VertexBuffer::create_vertex_buf::<RectVertex>(
             &gpu_context.device,
             vec![rect_vertex],
             RECT_INDEX,
-        );
-        rect_vertex
+        )
+    }
+
+    fn get_rect(&self) -> Option<Rectangle> {
+        Some(*self)
     }
 }
 
 impl ShapeGraph for Circle {
     fn to_buffer(&self, gpu_context: &GPUContext, style: Style) -> VertexBuffer {
-        let circle_vertex = CircleVertex::new(&self, 0, style.get_display_color());
-        let cricle_buffer = VertexBuffer::create_vertex_buf::<CircleVertex>(
+        let circle_vertex = CircleVertex::new(self, 0, &style.get_paint());
+        VertexBuffer::create_vertex_buf::<CircleVertex>(
             &gpu_context.device,
             vec![circle_vertex],
             RECT_INDEX,
-        );
-        cricle_buffer
+        )
     }
 }
 
 impl ShapeGraph for RegularPolygon {
     fn to_buffer(&self, gpu_context: &GPUContext, style: Style) -> VertexBuffer {
-        let circle_vertex = CircleVertex::new(&self.point, self.edge, style.get_display_color());
-        let cricle_buffer = VertexBuffer::create_vertex_buf::<CircleVertex>(
+        let circle_vertex = CircleVertex::new(&self.point, self.edge, &style.get_paint());
+        VertexBuffer::create_vertex_buf::<CircleVertex>(
             &gpu_context.device,
             vec![circle_vertex],
             RECT_INDEX,
-        );
-        cricle_buffer
+        )
     }
 }
 
 impl ShapeGraph for Polygon {
     fn to_buffer(&self, gpu_context: &GPUContext, style: Style) -> VertexBuffer {
-        PointVertex::from_shape_to_vector(gpu_context, &self.points, style.get_display_color())
+        let geometry = fill_polygon(self, &style.get_paint(), gpu_context.get_logical_size());
+        PointVertex::from_geometry(gpu_context, geometry)
+    }
+}
+
+impl ShapeGraph for Line {
+    fn to_buffer(&self, gpu_context: &GPUContext, style: Style) -> VertexBuffer {
+        let path = build_polyline(&[self.start, self.end], false);
+        let geometry = stroke_path(&path, &self.stroke, &style.get_paint());
+        PointVertex::from_geometry(gpu_context, geometry)
+    }
+}
+
+impl ShapeGraph for Polyline {
+    fn to_buffer(&self, gpu_context: &GPUContext, style: Style) -> VertexBuffer {
+        let path = build_polyline(&self.points, self.closed);
+        let geometry = stroke_path(&path, &self.stroke, &style.get_paint());
+        PointVertex::from_geometry(gpu_context, geometry)
+    }
+}
+
+impl ShapeGraph for Path {
+    fn to_buffer(&self, gpu_context: &GPUContext, style: Style) -> VertexBuffer {
+        let path = build_path(&self.segments);
+        let paint = style.get_paint();
+        let geometry = match &self.stroke {
+            Some(stroke) => stroke_path(&path, stroke, &paint),
+            None => fill_path(&path, self.fill_rule, &paint),
+        };
+        PointVertex::from_geometry(gpu_context, geometry)
     }
 }
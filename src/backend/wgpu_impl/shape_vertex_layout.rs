@@ -2,7 +2,75 @@ use wgpu::*;
 
 use crate::backend::wgpu_impl::*;
 use crate::graphic::base::*;
-use crate::graphic::style::{Bordering, Rounding, Style};
+use crate::graphic::style::{BorderLine, Bordering, BoxShadow, Style};
+
+/// 纯色填充
+const SOLID_PAINT: u32 = 0;
+/// 线性渐变填充
+const LINEAR_PAINT: u32 = 1;
+/// 径向渐变填充
+const RADIAL_PAINT: u32 = 2;
+
+/// 填充颜色顶点数据，嵌入各图形的顶点结构体中，由paint.wgsl解析
+/// 渐变参数均为像素坐标：线性渐变为起点和终点，径向渐变为圆心和半径
+#[repr(C)]
+#[derive(Copy, Default, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct PaintVertex {
+    /// 填充类型及色标数量
+    pub kind: [u32; 2],
+    pub params: [f32; 4],
+    pub offsets: [f32; MAX_GRADIENT_STOPS],
+    pub colors: [[f32; 4]; MAX_GRADIENT_STOPS],
+}
+
+impl PaintVertex {
+    /// 参数：填充颜色，图形外接矩形的左上顶点，外接矩形的宽高
+    pub fn new(paint: &Paint, origin: Point<f32>, size: Point<f32>) -> Self {
+        let mut vertex = PaintVertex::default();
+        match paint {
+            Paint::Solid(color) => {
+                vertex.kind = [SOLID_PAINT, 1];
+                vertex.colors[0] = color.to_vec();
+            }
+            Paint::Gradient(gradient) => {
+                let center = Point::new(origin.x + size.x / 2.0, origin.y + size.y / 2.0);
+                match gradient.kind {
+                    GradientKind::Linear { angle } => {
+                        // 渐变线穿过中心，长度恰好使两端的垂线经过外接矩形的角
+                        let (sin, cos) = angle.to_radians().sin_cos();
+                        let half = (size.x * cos.abs() + size.y * sin.abs()) / 2.0;
+                        vertex.kind[0] = LINEAR_PAINT;
+                        vertex.params = [
+                            center.x - cos * half,
+                            center.y - sin * half,
+                            center.x + cos * half,
+                            center.y + sin * half,
+                        ];
+                    }
+                    GradientKind::Radial {
+                        center: relative,
+                        radius,
+                    } => {
+                        vertex.kind[0] = RADIAL_PAINT;
+                        vertex.params = [
+                            origin.x + relative.x * size.x,
+                            origin.y + relative.y * size.y,
+                            radius * size.x.max(size.y),
+                            0.0,
+                        ];
+                    }
+                }
+                let stops = gradient.stops();
+                vertex.kind[1] = stops.len() as u32;
+                for (i, stop) in stops.iter().enumerate() {
+                    vertex.offsets[i] = stop.offset;
+                    vertex.colors[i] = stop.color.to_vec();
+                }
+            }
+        }
+        vertex
+    }
+}
 
 /// 圆形顶点数据布局结构体
 /// 顶点顺序为左下开始逆时针排序
@@ -10,28 +78,42 @@ use crate::graphic::style::{Bordering, Rounding, Style};
 #[derive(Copy, Default, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct CircleVertex {
     pub position: [f32; 2],
-    pub color: [f32; 4],
     pub radius: f32,
     pub edge: u32,
+    pub paint: PaintVertex,
 }
 
 impl CircleVertex {
-    pub fn new(point: &Circle, edge: u32, color: RGBA) -> Self {
+    pub fn new(point: &Circle, edge: u32, paint: &Paint) -> Self {
         log::info!("create the PolygonVertex obj");
+        let diameter = point.radius * 2.0;
         Self {
             position: [point.position.x, point.position.y],
-            color: color.to_vec(),
             radius: point.radius,
             edge,
+            paint: PaintVertex::new(
+                paint,
+                Point::new(
+                    point.position.x - point.radius,
+                    point.position.y - point.radius,
+                ),
+                Point::new(diameter, diameter),
+            ),
         }
     }
 }
 
-const CIRCLE_ATTRS: [VertexAttribute; 4] = wgpu::vertex_attr_array![
+const CIRCLE_ATTRS: [VertexAttribute; 10] = wgpu::vertex_attr_array![
                 0 => Float32x2,
-                1 => Float32x4,
-                2 => Float32,
-                3 => Uint32];
+                1 => Float32,
+                2 => Uint32,
+                3 => Uint32x2,
+                4 => Float32x4,
+                5 => Float32x4,
+                6 => Float32x4,
+                7 => Float32x4,
+                8 => Float32x4,
+                9 => Float32x4];
 
 impl VertexLayout for CircleVertex {
     fn set_vertex_desc<'a>() -> VertexBufferLayout<'a> {
@@ -49,10 +131,11 @@ impl VertexLayout for CircleVertex {
     fn get_shader(device: &Device) -> ShaderModule {
         device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("circle shader"),
-            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(include_str!(concat!(
-                env!("CARGO_MANIFEST_DIR"),
-                "/shader_c/circle.wgsl"
-            )))),
+            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(shader_source!(
+                "globals.wgsl",
+                "paint.wgsl",
+                "circle.wgsl"
+            ))),
         })
     }
 }
@@ -64,16 +147,26 @@ pub struct RectVertex {
     pub size: [f32; 2],
     pub position: [f32; 2],
     pub border_color: [f32; 4],
-    pub rect_color: [f32; 4],
-    pub is_round_or_border: [u32; 2],
+    /// 各角圆角半径，依次为左上、右上、右下、左下
+    pub radii: [f32; 4],
+    /// 边框宽度，虚线长度，虚线间隔，实线时虚线长度为0
+    pub border: [f32; 3],
+    pub paint: PaintVertex,
 }
 
-const RECT_ATTRS: [VertexAttribute; 5] = wgpu::vertex_attr_array![
+const RECT_ATTRS: [VertexAttribute; 12] = wgpu::vertex_attr_array![
                 0 => Float32x2,
                 1 => Float32x2,
                 2 => Float32x4,
                 3 => Float32x4,
-                4 => Uint32x2];
+                4 => Float32x3,
+                5 => Uint32x2,
+                6 => Float32x4,
+                7 => Float32x4,
+                8 => Float32x4,
+                9 => Float32x4,
+                10 => Float32x4,
+                11 => Float32x4];
 
 impl VertexLayout for RectVertex {
     fn set_vertex_desc<'a>() -> VertexBufferLayout<'a> {
@@ -91,10 +184,12 @@ impl VertexLayout for RectVertex {
     fn get_shader(device: &Device) -> ShaderModule {
         device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("round_rect shader"),
-            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(include_str!(concat!(
-                env!("CARGO_MANIFEST_DIR"),
-                "/shader_c/round_rect.wgsl"
-            )))),
+            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(shader_source!(
+                "globals.wgsl",
+                "paint.wgsl",
+                "sdf.wgsl",
+                "round_rect.wgsl"
+            ))),
         })
     }
 }
@@ -102,26 +197,25 @@ impl VertexLayout for RectVertex {
 impl RectVertex {
     pub fn new(rect: &Rectangle, style: Style) -> RectVertex {
         let mut border_color = [0.0, 0.0, 0.0, 0.0];
-        let rect_color = style.get_display_color().to_vec();
-        let is_round;
-        let is_border;
-        match style.get_round() {
-            Rounding::Round => is_round = 1,
-            Rounding::NoRound => is_round = 0,
-        }
-        match style.get_border() {
-            Bordering::Border(color) => {
-                is_border = 1;
-                border_color = color.to_vec()
-            }
-            Bordering::NoBorder => is_border = 0,
+        let mut border = [0.0, 0.0, 0.0];
+        if let Bordering::Border { color, width, line } = *style.get_border() {
+            border_color = color.to_vec();
+            border = match line {
+                BorderLine::Solid => [width, 0.0, 0.0],
+                BorderLine::Dashed(dash, gap) => [width, dash.max(0.0), gap.max(0.0)],
+            };
         }
         RectVertex {
             size: [rect.width as f32, rect.height as f32],
             position: [rect.position.x, rect.position.y],
             border_color,
-            rect_color,
-            is_round_or_border: [is_round, is_border],
+            radii: corner_radii(rect, &style),
+            border,
+            paint: PaintVertex::new(
+                &style.get_paint(),
+                rect.position,
+                Point::new(rect.width as f32, rect.height as f32),
+            ),
         }
     }
 }
@@ -131,7 +225,7 @@ impl RectVertex {
 #[derive(Copy, Default, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct PointVertex {
     pub position: [f32; 2],
-    pub color: [f32; 4],
+    pub paint: PaintVertex,
 }
 
 impl PointVertex {
@@ -139,14 +233,24 @@ impl PointVertex {
         log::info!("create the PointVertex obj");
         Self {
             position: [x, y],
-            color: color.to_vec(),
+            paint: PaintVertex::new(
+                &Paint::Solid(color),
+                Point::new(0.0, 0.0),
+                Point::new(0.0, 0.0),
+            ),
         }
     }
 }
 
-const POINT_ATTRS: [VertexAttribute; 2] = wgpu::vertex_attr_array![
+const POINT_ATTRS: [VertexAttribute; 8] = wgpu::vertex_attr_array![
                 0 => Float32x2,
-                1 => Float32x4 ];
+                1 => Uint32x2,
+                2 => Float32x4,
+                3 => Float32x4,
+                4 => Float32x4,
+                5 => Float32x4,
+                6 => Float32x4,
+                7 => Float32x4 ];
 
 impl VertexLayout for PointVertex {
     fn set_vertex_desc<'a>() -> VertexBufferLayout<'a> {
@@ -164,38 +268,178 @@ impl VertexLayout for PointVertex {
     fn get_shader(device: &Device) -> ShaderModule {
         device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("triangle shader"),
-            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(include_str!(concat!(
-                env!("CARGO_MANIFEST_DIR"),
-                "/shader_c/triangle.wgsl"
-            )))),
+            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(shader_source!(
+                "globals.wgsl",
+                "paint.wgsl",
+                "triangle.wgsl"
+            ))),
         })
     }
 }
 
 impl PointVertex {
-    pub fn from_shape_to_vector(
+    /// 由细分结果创建顶点缓冲，细分失败时不绘制任何内容
+    pub fn from_geometry(
         gpu_context: &WGPUContext,
-        points: &Vec<Point<f32>>,
-        color: RGBA,
+        geometry: Result<Geometry, ShapeError>,
     ) -> VertexBuffer {
-        let vertex_nums = (points.len() - 3) * 2 + points.len();
-        let mut vect = Vec::with_capacity(points.len());
-        let mut indices = Vec::with_capacity(vertex_nums);
-        for i in 0..points.len() {
-            vect.push(PointVertex::new(points[i].x, points[i].y, color));
+        let geometry = geometry.unwrap_or_else(|error| {
+            log::error!("tessellate shape failed: {}", error);
+            Geometry::new()
+        });
+        VertexBuffer::create_vertex_buf::<PointVertex>(
+            &gpu_context.device,
+            geometry.vertices,
+            geometry.indices.as_slice(),
+        )
+    }
+}
+
+/// 获取样式对应的矩形各角圆角半径
+fn corner_radii(rect: &Rectangle, style: &Style) -> [f32; 4] {
+    style
+        .get_round()
+        .get_radii(rect.width as f32, rect.height as f32)
+}
+
+/// 阴影顶点数据布局结构体
+#[repr(C)]
+#[derive(Copy, Default, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct ShadowVertex {
+    pub position: [f32; 2],
+    pub size: [f32; 2],
+    pub offset: [f32; 2],
+    pub color: [f32; 4],
+    pub radii: [f32; 4],
+    pub blur: f32,
+    pub spread: f32,
+}
+
+const SHADOW_ATTRS: [VertexAttribute; 7] = wgpu::vertex_attr_array![
+                0 => Float32x2,
+                1 => Float32x2,
+                2 => Float32x2,
+                3 => Float32x4,
+                4 => Float32x4,
+                5 => Float32,
+                6 => Float32];
+
+impl VertexLayout for ShadowVertex {
+    fn set_vertex_desc<'a>() -> VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<ShadowVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &SHADOW_ATTRS,
         }
-        let mut i = 1u16;
-        while i < points.len() as u16 - 1 {
-            indices.push(0);
-            indices.push(i);
-            i = i + 1;
-            indices.push(i);
+    }
+
+    fn get_shape_type() -> ShapeType {
+        ShapeType::SHADOW
+    }
+
+    fn get_shader(device: &Device) -> ShaderModule {
+        device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("shadow shader"),
+            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(shader_source!(
+                "globals.wgsl",
+                "sdf.wgsl",
+                "shadow.wgsl"
+            ))),
+        })
+    }
+}
+
+impl ShadowVertex {
+    /// 创建矩形阴影顶点缓冲
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(
+        gpu_context: &WGPUContext,
+        rect: &Rectangle,
+        style: &Style,
+        shadow: &BoxShadow,
+    ) -> VertexBuffer {
+        let shadow_vertex = ShadowVertex {
+            position: [rect.position.x, rect.position.y],
+            size: [rect.width as f32, rect.height as f32],
+            offset: [shadow.offset.x, shadow.offset.y],
+            color: shadow.color.to_vec(),
+            radii: corner_radii(rect, style),
+            blur: shadow.blur.max(0.0),
+            spread: shadow.spread,
+        };
+        VertexBuffer::create_vertex_buf::<ShadowVertex>(
+            &gpu_context.device,
+            vec![shadow_vertex],
+            RECT_INDEX,
+        )
+    }
+}
+
+/// 背景模糊顶点数据布局结构体
+#[repr(C)]
+#[derive(Copy, Default, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct BlurVertex {
+    pub position: [f32; 2],
+    pub size: [f32; 2],
+    pub radii: [f32; 4],
+    pub blur: f32,
+}
+
+const BLUR_ATTRS: [VertexAttribute; 4] = wgpu::vertex_attr_array![
+                0 => Float32x2,
+                1 => Float32x2,
+                2 => Float32x4,
+                3 => Float32];
+
+impl VertexLayout for BlurVertex {
+    fn set_vertex_desc<'a>() -> VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<BlurVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &BLUR_ATTRS,
         }
-        let point_buffer = VertexBuffer::create_vertex_buf::<PointVertex>(
+    }
+
+    fn get_shape_type() -> ShapeType {
+        ShapeType::BLUR
+    }
+
+    fn get_shader(device: &Device) -> ShaderModule {
+        device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("blur shader"),
+            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(shader_source!(
+                "globals.wgsl",
+                "sdf.wgsl",
+                "blur.wgsl"
+            ))),
+        })
+    }
+
+    fn set_pipeline_layout(device: &Device) -> PipelineLayout {
+        let globals_bind_group_layout = device.create_bind_group_layout(GLOBALS_BIND_GROUP_LAYOUT);
+        let texture_bind_group_layout = device.create_bind_group_layout(DEFAULT_BIND_GROUP_LAYOUT);
+        device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Blur Pipeline Layout"),
+            bind_group_layouts: &[&globals_bind_group_layout, &texture_bind_group_layout],
+            push_constant_ranges: &[],
+        })
+    }
+}
+
+impl BlurVertex {
+    /// 创建矩形背景模糊顶点缓冲
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(gpu_context: &WGPUContext, rect: &Rectangle, style: &Style) -> VertexBuffer {
+        let blur_vertex = BlurVertex {
+            position: [rect.position.x, rect.position.y],
+            size: [rect.width as f32, rect.height as f32],
+            radii: corner_radii(rect, style),
+            blur: style.get_backdrop_blur(),
+        };
+        VertexBuffer::create_vertex_buf::<BlurVertex>(
             &gpu_context.device,
-            vect,
-            indices.as_slice(),
-        );
-        point_buffer
+            vec![blur_vertex],
+            RECT_INDEX,
+        )
     }
 }
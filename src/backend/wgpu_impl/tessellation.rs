@@ -0,0 +1,201 @@
+use lyon::algorithms::aabb::bounding_box;
+use lyon::algorithms::measure::{PathMeasurements, SampleType};
+use lyon::math::{point, vector, Angle};
+use lyon::tessellation::{
+    BuffersBuilder, FillOptions, FillTessellator, FillVertex, StrokeOptions, StrokeTessellator,
+    StrokeVertex, TessellationError, VertexBuffers,
+};
+
+use crate::backend::wgpu_impl::*;
+use crate::graphic::base::*;
+
+/// 曲线细分容差（像素）
+const TOLERANCE: f32 = 0.1;
+
+/// 细分得到的三角形顶点及索引
+pub type Geometry = VertexBuffers<PointVertex, u16>;
+
+fn to_point(p: Point<f32>) -> lyon::math::Point {
+    point(p.x, p.y)
+}
+
+impl From<TessellationError> for ShapeError {
+    fn from(error: TessellationError) -> Self {
+        ShapeError::Tessellation(error.to_string())
+    }
+}
+
+/// 将路径片段转换为lyon路径
+pub fn build_path(segments: &[PathSegment]) -> lyon::path::Path {
+    let mut builder = lyon::path::Path::builder().with_svg();
+    for segment in segments {
+        match *segment {
+            PathSegment::MoveTo(to) => {
+                builder.move_to(to_point(to));
+            }
+            PathSegment::LineTo(to) => {
+                builder.line_to(to_point(to));
+            }
+            PathSegment::QuadTo { ctrl, to } => {
+                builder.quadratic_bezier_to(to_point(ctrl), to_point(to));
+            }
+            PathSegment::CubicTo { ctrl1, ctrl2, to } => {
+                builder.cubic_bezier_to(to_point(ctrl1), to_point(ctrl2), to_point(to));
+            }
+            PathSegment::ArcTo {
+                center,
+                radius,
+                sweep_angle,
+            } => builder.arc(
+                to_point(center),
+                vector(radius.x, radius.y),
+                Angle::radians(sweep_angle),
+                Angle::radians(0.0),
+            ),
+            PathSegment::Close => builder.close(),
+        }
+    }
+    builder.build()
+}
+
+/// 将折线顶点转换为lyon路径
+pub fn build_polyline(points: &[Point<f32>], closed: bool) -> lyon::path::Path {
+    let mut builder = lyon::path::Path::builder();
+    builder.add_polygon(lyon::path::Polygon {
+        points: &points.iter().map(|p| to_point(*p)).collect::<Vec<_>>(),
+        closed,
+    });
+    builder.build()
+}
+
+/// 将多边形外轮廓及孔洞转换为lyon路径，顶点坐标由-1.0~1.0映射为逻辑像素坐标
+fn build_polygon(polygon: &Polygon, size: Point<f32>) -> lyon::path::Path {
+    let to_pixel = |p: &Point<f32>| point((p.x + 1.0) / 2.0 * size.x, (1.0 - p.y) / 2.0 * size.y);
+    let mut builder = lyon::path::Path::builder();
+    for contour in std::iter::once(&polygon.points).chain(polygon.holes.iter()) {
+        builder.add_polygon(lyon::path::Polygon {
+            points: &contour.iter().map(to_pixel).collect::<Vec<_>>(),
+            closed: true,
+        });
+    }
+    builder.build()
+}
+
+/// 按虚线模式截取路径，得到由多段实线组成的新路径
+fn dash_path(path: &lyon::path::Path, dash: &[f32], offset: f32) -> lyon::path::Path {
+    // 奇数个元素的模式按svg规范重复一次
+    let pattern = if dash.len() % 2 == 1 {
+        dash.repeat(2)
+    } else {
+        dash.to_vec()
+    };
+    let period: f32 = pattern.iter().sum();
+    let measurements = PathMeasurements::from_path(path, TOLERANCE);
+    let mut sampler = measurements.create_sampler(path, SampleType::Distance);
+    let length = sampler.length();
+    let mut builder = lyon::path::Path::builder();
+    if period <= 0.0 || length <= 0.0 {
+        return path.clone();
+    }
+    let mut distance = -offset.rem_euclid(period);
+    let mut index = 0;
+    while distance < length {
+        let (on, off) = (pattern[index], pattern[index + 1]);
+        if distance + on > 0.0 {
+            sampler.split_range(distance.max(0.0)..distance + on, &mut builder);
+        }
+        distance += on + off;
+        index = (index + 2) % pattern.len();
+    }
+    builder.build()
+}
+
+/// 以路径的外接矩形作为渐变的参照区域
+fn path_paint(path: &lyon::path::Path, paint: &Paint) -> PaintVertex {
+    let bounds = bounding_box(path.iter());
+    PaintVertex::new(
+        paint,
+        Point::new(bounds.min.x, bounds.min.y),
+        Point::new(bounds.width(), bounds.height()),
+    )
+}
+
+/// 由像素坐标创建顶点，由着色器按当前变换映射到屏幕
+fn pixel_vertex(position: lyon::math::Point, paint: PaintVertex) -> PointVertex {
+    PointVertex {
+        position: [position.x, position.y],
+        paint,
+    }
+}
+
+/// 细分路径描边
+/// 参数：路径，描边样式，填充颜色
+pub fn stroke_path(
+    path: &lyon::path::Path,
+    stroke: &Stroke,
+    paint: &Paint,
+) -> Result<Geometry, ShapeError> {
+    let options = StrokeOptions::tolerance(TOLERANCE)
+        .with_line_width(stroke.width)
+        .with_line_cap(match stroke.cap {
+            LineCap::Butt => lyon::tessellation::LineCap::Butt,
+            LineCap::Square => lyon::tessellation::LineCap::Square,
+            LineCap::Round => lyon::tessellation::LineCap::Round,
+        })
+        .with_line_join(match stroke.join {
+            LineJoin::Miter => lyon::tessellation::LineJoin::Miter,
+            LineJoin::Round => lyon::tessellation::LineJoin::Round,
+            LineJoin::Bevel => lyon::tessellation::LineJoin::Bevel,
+        });
+    let paint = path_paint(path, paint);
+    let dashed;
+    let path = if stroke.dash.is_empty() {
+        path
+    } else {
+        dashed = dash_path(path, &stroke.dash, stroke.dash_offset);
+        &dashed
+    };
+    let mut geometry = Geometry::new();
+    StrokeTessellator::new().tessellate_path(
+        path,
+        &options,
+        &mut BuffersBuilder::new(&mut geometry, |vertex: StrokeVertex| {
+            pixel_vertex(vertex.position(), paint)
+        }),
+    )?;
+    Ok(geometry)
+}
+
+/// 细分路径填充
+/// 参数：路径，填充规则，填充颜色
+pub fn fill_path(
+    path: &lyon::path::Path,
+    fill_rule: FillRule,
+    paint: &Paint,
+) -> Result<Geometry, ShapeError> {
+    let options = FillOptions::tolerance(TOLERANCE).with_fill_rule(match fill_rule {
+        FillRule::EvenOdd => lyon::tessellation::FillRule::EvenOdd,
+        FillRule::NonZero => lyon::tessellation::FillRule::NonZero,
+    });
+    let paint = path_paint(path, paint);
+    let mut geometry = Geometry::new();
+    FillTessellator::new().tessellate_path(
+        path,
+        &options,
+        &mut BuffersBuilder::new(&mut geometry, |vertex: FillVertex| {
+            pixel_vertex(vertex.position(), paint)
+        }),
+    )?;
+    Ok(geometry)
+}
+
+/// 细分多边形填充，顶点不足或坐标无效时返回错误
+/// 参数：多边形，填充颜色，窗口逻辑尺寸
+pub fn fill_polygon(
+    polygon: &Polygon,
+    paint: &Paint,
+    size: Point<f32>,
+) -> Result<Geometry, ShapeError> {
+    polygon.validate()?;
+    fill_path(&build_polygon(polygon, size), polygon.fill_rule, paint)
+}
@@ -1,4 +1,6 @@
+use std::collections::HashMap;
 use std::num::NonZeroU32;
+use std::rc::Rc;
 
 use wgpu::TextureFormat;
 
@@ -41,11 +43,10 @@ impl GTexture {
         };
         // 注：图像纹理导入后会被转化为包含每个像素点rgba颜色值的一维数组
         // 因此行数宽度为图像宽度*4，列数宽度不变
-        let image_width: u32;
-        match texture_format {
-            TextureFormat::R8Unorm => image_width = data_size.x,
-            _ => image_width = data_size.x * 4,
-        }
+        let image_width = match texture_format {
+            TextureFormat::R8Unorm => data_size.x,
+            _ => data_size.x * 4,
+        };
         let image_layout = wgpu::ImageDataLayout {
             offset: 0,
             bytes_per_row: NonZeroU32::new(image_width),
@@ -98,6 +99,64 @@ impl GTexture {
     }
 }
 
+/// 图像纹理缓存
+/// 按图像来源及栅格化尺寸缓存已上传的纹理，来源不变的图像每帧复用同一纹理，
+/// 每帧结束时释放本帧没有绘制的图像纹理
+#[derive(Debug, Default)]
+pub struct ImageCache {
+    entries: HashMap<ImageKey, CachedImage>,
+}
+
+/// 缓存的图像纹理
+#[derive(Debug)]
+struct CachedImage {
+    /// 图像来源，持有来源使缓存键中的指针在缓存期间不被复用
+    _source: ImageSource,
+    buffer: Rc<TextureBufferData>,
+    /// 本帧是否绘制过
+    used: bool,
+}
+
+impl ImageCache {
+    /// 获取图像的纹理缓冲，缓存中没有时加载并上传图像
+    /// size为显示区域的物理像素尺寸，图像无法加载时记录错误并显示透明的占位图像
+    pub fn get_or_upload(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        source: &ImageSource,
+        size: Point<u32>,
+    ) -> Rc<TextureBufferData> {
+        let entry = self
+            .entries
+            .entry(source.key(size.x, size.y))
+            .or_insert_with(|| {
+                let image = source.to_raw(size.x, size.y).unwrap_or_else(|error| {
+                    log::error!("failed to load image {:?}: {}", source, error);
+                    ImageRaw::placeholder()
+                });
+                let mut texture = GTexture::new(
+                    device,
+                    Point::new(image.width, image.height),
+                    TextureFormat::Rgba8UnormSrgb,
+                );
+                CachedImage {
+                    _source: source.clone(),
+                    buffer: Rc::new(texture.create_bind_group(device, queue, image)),
+                    used: false,
+                }
+            });
+        entry.used = true;
+        entry.buffer.clone()
+    }
+
+    /// 释放本帧没有绘制的图像纹理，在每帧显示后调用
+    pub fn trim(&mut self) {
+        self.entries
+            .retain(|_, entry| std::mem::replace(&mut entry.used, false));
+    }
+}
+
 /// 定义纹理描述符
 /// 参数：纹理尺寸
 /// 输出配置：定义纹理尺寸，维度：2d，颜色格式：rgba，纹理来源：sampled,copy_dst
@@ -114,10 +173,167 @@ pub fn create_2d_texture(
         dimension: wgpu::TextureDimension::D2,
         format: texture_format,
         usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
-        view_formats: &[TextureFormat::R8Unorm],
+        view_formats: &[],
     })
 }
 
+/// 创建多重采样渲染目标
+/// 参数：目标尺寸，颜色格式，采样数
+/// 渲染时先绘制到此纹理，再解析到交换缓冲区
+pub fn create_multisampled_view(
+    device: &wgpu::Device,
+    size: Point<u32>,
+    texture_format: wgpu::TextureFormat,
+    sample_count: u32,
+) -> wgpu::TextureView {
+    device
+        .create_texture(&wgpu::TextureDescriptor {
+            label: Some("multisampled frame"),
+            size: wgpu::Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: texture_format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        })
+        .create_view(&wgpu::TextureViewDescriptor::default())
+}
+
+/// 离屏渲染目标结构体
+/// 既可作为渲染目标，也可作为纹理被采样或复制
+#[derive(Debug)]
+pub struct RenderTarget {
+    pub texture: wgpu::Texture,
+    pub view: wgpu::TextureView,
+    /// 采样此目标时使用的纹理缓冲
+    pub buffer: TextureBufferData,
+    pub size: Point<u32>,
+}
+
+impl RenderTarget {
+    /// 创建离屏渲染目标
+    /// 参数：目标尺寸，颜色格式
+    pub fn new(device: &wgpu::Device, size: Point<u32>, texture_format: TextureFormat) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("render target"),
+            size: wgpu::Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: texture_format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC
+                | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = device.create_sampler(DEFAULT_TEXTURE_SAMPLER);
+        let layout = device.create_bind_group_layout(DEFAULT_BIND_GROUP_LAYOUT);
+        let buffer = TextureBufferData {
+            width: size.x,
+            height: size.y,
+            uniform: bind_group(device, &layout, &view, &sampler),
+        };
+        RenderTarget {
+            texture,
+            view,
+            buffer,
+            size,
+        }
+    }
+
+    /// 将渲染目标内容读回内存，转换为RGBA图像数据
+    pub fn read_pixels(&self, device: &wgpu::Device, queue: &wgpu::Queue) -> ImageRaw {
+        let (width, height) = (self.size.x, self.size.y);
+        // 复制到缓冲区时每行字节数需按256对齐
+        let row_size = width * 4;
+        let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let padded_row_size = row_size.div_ceil(align) * align;
+        let output = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("capture buffer"),
+            size: (padded_row_size * height) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Capture Encoder"),
+        });
+        encoder.copy_texture_to_buffer(
+            self.texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &output,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: NonZeroU32::new(padded_row_size),
+                    rows_per_image: NonZeroU32::new(height),
+                },
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+        queue.submit(Some(encoder.finish()));
+
+        let slice = output.slice(..);
+        let (sender, receiver) = futures::channel::oneshot::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            sender.send(result).ok();
+        });
+        device.poll(wgpu::Maintain::Wait);
+        futures::executor::block_on(receiver)
+            .expect("capture buffer mapping was cancelled")
+            .expect("Failed to map capture buffer");
+
+        let is_bgra = matches!(
+            self.texture.format(),
+            TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb
+        );
+        let mut data = Vec::with_capacity((row_size * height) as usize);
+        {
+            let mapped = slice.get_mapped_range();
+            for row in mapped.chunks(padded_row_size as usize) {
+                data.extend_from_slice(&row[..row_size as usize]);
+            }
+        }
+        output.unmap();
+        if is_bgra {
+            for pixel in data.chunks_mut(4) {
+                pixel.swap(0, 2);
+            }
+        }
+        ImageRaw {
+            width,
+            height,
+            data,
+        }
+    }
+
+    /// 复制另一个尺寸相同的渲染目标的内容
+    pub fn copy_from(&self, encoder: &mut wgpu::CommandEncoder, source: &RenderTarget) {
+        encoder.copy_texture_to_texture(
+            source.texture.as_image_copy(),
+            self.texture.as_image_copy(),
+            wgpu::Extent3d {
+                width: self.size.x.min(source.size.x),
+                height: self.size.y.min(source.size.y),
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+}
+
 /// 将图像原始数据写入到纹理缓冲空间中
 pub fn writer_data_to_texture(
     queue: &wgpu::Queue,
@@ -132,7 +348,7 @@ pub fn writer_data_to_texture(
         image_layout,
         size,
     );
-    return texture.create_view(&wgpu::TextureViewDescriptor::default());
+    texture.create_view(&wgpu::TextureViewDescriptor::default())
 }
 
 /// 描述纹理顶点数据布局,用于着色器识别数据
@@ -143,7 +359,7 @@ pub fn bind_group(
     sampler: &wgpu::Sampler,
 ) -> wgpu::BindGroup {
     device.create_bind_group(&wgpu::BindGroupDescriptor {
-        layout: &bind_group_layout,
+        layout: bind_group_layout,
         entries: &[
             wgpu::BindGroupEntry {
                 binding: 0,
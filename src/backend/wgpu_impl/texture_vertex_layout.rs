@@ -7,15 +7,27 @@ use crate::graphic::base::{Rectangle, RGBA};
 #[repr(C)]
 #[derive(Copy, Default, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct TextureVertex {
+    /// 左上顶点的像素坐标
     pub position: [f32; 2],
+    /// 纹理区域的像素宽高
     pub tex_coords: [f32; 2],
     pub color: [f32; 4],
+    /// 纹理模式：0为单通道字形，1为RGBA图像，2为离屏图层
+    pub mode: u32,
 }
 
-const TEXTURE_ATTRS: [VertexAttribute; 3] = wgpu::vertex_attr_array![
+/// 单通道字形纹理，纹理值作为透明度
+pub const GLYPH_TEXTURE_MODE: u32 = 0;
+/// RGBA图像纹理，纹理颜色与顶点颜色相乘
+pub const IMAGE_TEXTURE_MODE: u32 = 1;
+/// 离屏图层纹理，纹理颜色为预乘透明度的颜色
+pub const LAYER_TEXTURE_MODE: u32 = 2;
+
+const TEXTURE_ATTRS: [VertexAttribute; 4] = wgpu::vertex_attr_array![
                 0 => Float32x2,
                 1 => Float32x2,
-                2 => Float32x4 ];
+                2 => Float32x4,
+                3 => Uint32 ];
 
 impl VertexLayout for TextureVertex {
     fn set_vertex_desc<'a>() -> VertexBufferLayout<'a> {
@@ -33,37 +45,58 @@ impl VertexLayout for TextureVertex {
     fn get_shader(device: &Device) -> ShaderModule {
         device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("texture shader"),
-            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(include_str!(concat!(
-                env!("CARGO_MANIFEST_DIR"),
-                "/shader_c/image.wgsl"
-            )))),
+            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(shader_source!(
+                "globals.wgsl",
+                "image.wgsl"
+            ))),
         })
     }
 
     fn set_pipeline_layout(device: &Device) -> PipelineLayout {
+        let globals_bind_group_layout = device.create_bind_group_layout(GLOBALS_BIND_GROUP_LAYOUT);
         let texture_bind_group_layout = device.create_bind_group_layout(DEFAULT_BIND_GROUP_LAYOUT);
-        let render_pipeline_layout =
-            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                label: Some("Render Pipeline Layout"),
-                bind_group_layouts: &[&texture_bind_group_layout],
-                push_constant_ranges: &[],
-            });
-        return render_pipeline_layout;
+        device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Render Pipeline Layout"),
+            bind_group_layouts: &[&globals_bind_group_layout, &texture_bind_group_layout],
+            push_constant_ranges: &[],
+        })
     }
 }
 
 impl TextureVertex {
+    /// 创建字形纹理顶点缓冲
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(gpu_context: &WGPUContext, rect: &Rectangle, font_color: RGBA) -> VertexBuffer {
-        let sc_desc = gpu_context.get_surface_size();
-        let (t_x, t_y, t_w, t_h) = rect.get_coord(sc_desc.x, sc_desc.y);
-        let color: [f32; 4] = font_color.to_vec();
+        Self::with_mode(gpu_context, rect, font_color, GLYPH_TEXTURE_MODE)
+    }
+
+    /// 创建图像纹理顶点缓冲
+    pub fn new_image(gpu_context: &WGPUContext, rect: &Rectangle, color: RGBA) -> VertexBuffer {
+        Self::with_mode(gpu_context, rect, color, IMAGE_TEXTURE_MODE)
+    }
+
+    /// 创建离屏图层纹理顶点缓冲
+    pub fn new_layer(gpu_context: &WGPUContext, rect: &Rectangle, opacity: f32) -> VertexBuffer {
+        Self::with_mode(
+            gpu_context,
+            rect,
+            RGBA(1.0, 1.0, 1.0, opacity),
+            LAYER_TEXTURE_MODE,
+        )
+    }
+
+    fn with_mode(
+        gpu_context: &WGPUContext,
+        rect: &Rectangle,
+        color: RGBA,
+        mode: u32,
+    ) -> VertexBuffer {
         let vect: Vec<TextureVertex> = vec![TextureVertex {
-            position: [t_x, t_y],
-            tex_coords: [t_w, t_h],
-            color,
+            position: [rect.position.x, rect.position.y],
+            tex_coords: [rect.width as f32, rect.height as f32],
+            color: color.to_vec(),
+            mode,
         }];
-        let vertex_buffer =
-            VertexBuffer::create_vertex_buf::<TextureVertex>(&gpu_context.device, vect, RECT_INDEX);
-        vertex_buffer
+        VertexBuffer::create_vertex_buf::<TextureVertex>(&gpu_context.device, vect, RECT_INDEX)
     }
 }
@@ -55,30 +55,16 @@ impl<'a> VertexBuffer {
         render_utils: &mut RenderUtil,
         texture_state: Option<&'a TextureBufferData>,
     ) {
-        // 获取顶点缓冲对应的渲染管道
-        let pipeline = render_utils.context.get_pipeline(self.shape_type).unwrap();
-        // 创建临时渲染变量，并设置渲染管道
-        let mut render_pass = create_render_pass(
-            &mut render_utils.encoder,
-            &render_utils.view,
-            RenderModel::Load,
-        );
-        render_pass.set_pipeline(&pipeline);
-        // 绑定纹理缓冲
-        if let Some(texture_buffer) = texture_state {
-            render_pass.set_bind_group(0, &texture_buffer.uniform, &[]);
-        }
-        // 设置顶点缓冲及其索引缓冲，并调用渲染方法
-        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-        render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
-        render_pass.draw_indexed(0..self.num_indices, 0, 0..1);
+        render_utils.draw_buffer(self, texture_state);
     }
 }
 
 /// 创建渲染中间变量
+/// 指定多重采样目标时，先绘制到多重采样目标，再解析到target中
 pub fn create_render_pass<'a>(
     encoder: &'a mut wgpu::CommandEncoder,
     target: &'a wgpu::TextureView,
+    msaa_target: Option<&'a wgpu::TextureView>,
     render_model: RenderModel,
 ) -> wgpu::RenderPass<'a> {
     let ops = match render_model {
@@ -98,10 +84,17 @@ pub fn create_render_pass<'a>(
     };
     let render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
         label: None,
-        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-            view: target,
-            resolve_target: None,
-            ops,
+        color_attachments: &[Some(match msaa_target {
+            Some(msaa_view) => wgpu::RenderPassColorAttachment {
+                view: msaa_view,
+                resolve_target: Some(target),
+                ops,
+            },
+            None => wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops,
+            },
         })],
         depth_stencil_attachment: None,
     });
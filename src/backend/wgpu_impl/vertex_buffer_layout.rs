@@ -10,22 +10,26 @@ pub trait VertexLayout: Sized {
     fn set_vertex_desc<'a>() -> wgpu::VertexBufferLayout<'a>;
     /// 获取顶点布局类型
     fn get_shape_type() -> ShapeType;
+    /// 获取图元拓扑类型，默认由顶点布局类型决定
+    fn get_topology() -> PrimitiveTopology {
+        get_fill_topology_by_type(Self::get_shape_type())
+    }
     /// 设置图元渲染器
     fn get_shader(device: &Device) -> ShaderModule;
-    /// 设置渲染管线布局
+    /// 设置渲染管线布局，绑定组0固定为变换等全局参数
     fn set_pipeline_layout(device: &Device) -> PipelineLayout {
-        let render_pipeline_layout =
-            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                label: Some("Render Pipeline Layout"),
-                bind_group_layouts: &[],
-                push_constant_ranges: &[],
-            });
-        return render_pipeline_layout;
+        let globals_bind_group_layout = device.create_bind_group_layout(GLOBALS_BIND_GROUP_LAYOUT);
+        device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Render Pipeline Layout"),
+            bind_group_layouts: &[&globals_bind_group_layout],
+            push_constant_ranges: &[],
+        })
     }
     /// 创建渲染管线
-    fn create_render_pipeline(device: &Device) -> RenderPipeline {
+    /// sample_count：多重采样数，需与渲染目标一致
+    fn create_render_pipeline(device: &Device, sample_count: u32) -> RenderPipeline {
         let shader = Self::get_shader(device);
-        let fill_topology = get_fill_topology_by_type(Self::get_shape_type());
+        let fill_topology = Self::get_topology();
         let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: Some("Render Pipeline"),
             layout: Some(&Self::set_pipeline_layout(device)),
@@ -37,11 +41,15 @@ pub trait VertexLayout: Sized {
             primitive: wgpu::PrimitiveState {
                 topology: fill_topology,
                 front_face: wgpu::FrontFace::Ccw,
-                cull_mode: Some(wgpu::Face::Back),
+                // 镜像变换会翻转顶点顺序，因此不剔除背面
+                cull_mode: None,
                 ..Default::default()
             },
             depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
             fragment: Some(wgpu::FragmentState {
                 module: &shader,
                 entry_point: "fs_main",
@@ -53,10 +61,29 @@ pub trait VertexLayout: Sized {
             }),
             multiview: None,
         });
-        return render_pipeline;
+        render_pipeline
     }
 }
 
+/// 全局参数绑定组描述符
+///
+/// 用途：向顶点及片段着色器传入当前变换及其逆变换、视口尺寸
+/// 各变换的参数存放在同一缓冲的不同位置，以动态偏移绑定
+pub const GLOBALS_BIND_GROUP_LAYOUT: &wgpu::BindGroupLayoutDescriptor =
+    &wgpu::BindGroupLayoutDescriptor {
+        entries: &[wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: true,
+                min_binding_size: None,
+            },
+            count: None,
+        }],
+        label: Some("globals_bind_group_layout"),
+    };
+
 /// 由形状类型获取指定的索引模式
 fn get_fill_topology_by_type(shape_type: ShapeType) -> PrimitiveTopology {
     match shape_type {
@@ -65,5 +92,16 @@ fn get_fill_topology_by_type(shape_type: ShapeType) -> PrimitiveTopology {
         ShapeType::BORDER => LineStrip,
         ShapeType::POINT => TriangleList,
         ShapeType::Circle => TriangleStrip,
+        ShapeType::SHADOW => TriangleStrip,
+        ShapeType::BLUR => TriangleStrip,
+        ShapeType::Custom(_) => TriangleList,
     }
 }
+
+/// 全局参数着色器源码，包含变换绑定组及坐标转换函数，
+/// 自定义着色器可拼接在其后使用`to_clip`等函数
+pub const GLOBALS_SHADER: &str = shader_source!("globals.wgsl");
+/// 填充着色器源码，提供纯色及渐变填充计算，需拼接在`GLOBALS_SHADER`之后
+pub const PAINT_SHADER: &str = shader_source!("paint.wgsl");
+/// 有向距离场着色器源码，提供圆角矩形距离及抗锯齿覆盖率计算，需拼接在`GLOBALS_SHADER`之后
+pub const SDF_SHADER: &str = shader_source!("sdf.wgsl");
@@ -1,7 +1,8 @@
 use std::fmt::Debug;
+use std::rc::Rc;
 
 use raw_window_handle;
-use wgpu::{Instance, RenderPipeline};
+use wgpu::{BindGroup, Instance, RenderPipeline};
 
 use crate::backend::wgpu_impl::*;
 use crate::graphic::base::*;
@@ -12,8 +13,8 @@ use crate::widget::ComponentModel;
 /// 作用：封装wgpu渲染所需的结构体
 #[derive(Debug)]
 pub struct WGPUContext {
-    /// 渲染面板
-    surface: wgpu::Surface,
+    /// 渲染面板，离屏渲染时为None
+    surface: Option<wgpu::Surface>,
     /// 图形设备
     pub device: wgpu::Device,
     /// 渲染命令队列
@@ -22,6 +23,26 @@ pub struct WGPUContext {
     sc_desc: wgpu::SurfaceConfiguration,
     /// 渲染管道
     glob_pipeline: PipelineState,
+    /// 多重采样数
+    sample_count: u32,
+    /// 窗口缩放比例，绘制时将逻辑坐标乘以此比例得到物理像素坐标
+    scale_factor: f32,
+    /// 多重采样渲染目标，采样数为1时不创建
+    pub(crate) msaa_view: Option<wgpu::TextureView>,
+    /// 离屏帧缓冲，每帧先绘制到此处，再复制到交换缓冲区
+    pub(crate) frame: RenderTarget,
+    /// 背景模糊时复制的帧内容，首次使用时创建
+    pub(crate) backdrop: Option<RenderTarget>,
+    /// 截图使用的离屏渲染目标，首次截图时创建
+    capture_target: Option<RenderTarget>,
+    /// 将离屏帧缓冲绘制到交换缓冲区的管道
+    pub(crate) blit_pipeline: RenderPipeline,
+    /// 图像纹理缓存
+    pub(crate) image_cache: ImageCache,
+    /// 全局参数缓冲池
+    pub(crate) globals: GlobalsPool,
+    /// 已弹出的离屏图层，按尺寸复用
+    pub(crate) layer_pool: Vec<Layer>,
 }
 
 impl WGPUContext {
@@ -30,73 +51,167 @@ impl WGPUContext {
     >(
         window: &W,
         window_size: Point<u32>,
+        sample_count: u32,
     ) -> WGPUContext {
         log::info!("Initializing the surface...");
-        let instance = Instance::new(wgpu::InstanceDescriptor {
-            backends: wgpu::Backends::all(),
-            dx12_shader_compiler: wgpu::Dx12Compiler::Fxc,
-        });
+        let instance = create_instance();
         let surface = unsafe { instance.create_surface(window).unwrap() };
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::HighPerformance,
-                force_fallback_adapter: false,
-                compatible_surface: Some(&surface),
-            })
-            .await
-            .expect("Request adapter");
-
+        let adapter = request_adapter(&instance, Some(&surface)).await;
         let caps = surface.get_capabilities(&adapter);
-        let formats = caps.formats;
-        let present_modes = caps.present_modes;
-        let alpha_modes = caps.alpha_modes;
-        let (device, queue) = adapter
-            .request_device(
-                &wgpu::DeviceDescriptor {
-                    label: None,
-                    features: wgpu::Features::empty(),
-                    limits: wgpu::Limits::downlevel_defaults().using_resolution(adapter.limits()),
-                },
-                None, // Trace path
-            )
-            .await
-            .unwrap();
+        let (device, queue) = request_device(&adapter).await;
+        let sc_desc =
+            surface_configuration(window_size, caps.present_modes[0], caps.alpha_modes[0]);
+        surface.configure(&device, &sc_desc);
+        Self::create(
+            Some(surface),
+            &adapter,
+            device,
+            queue,
+            sc_desc,
+            sample_count,
+        )
+    }
 
-        let sc_desc = wgpu::SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-            format: wgpu::TextureFormat::Bgra8UnormSrgb,
-            width: window_size.x,
-            height: window_size.y,
-            present_mode: present_modes[0],
-            alpha_mode: alpha_modes[0],
-            view_formats: vec![wgpu::TextureFormat::Bgra8UnormSrgb],
-        };
-        let glob_pipeline = PipelineState::default(&device);
+    /// 创建不依赖窗口的图形渲染上下文，只能绘制到离屏帧缓冲，用于截图
+    pub async fn headless(size: Point<u32>, sample_count: u32) -> WGPUContext {
+        log::info!("Initializing the headless context...");
+        let instance = create_instance();
+        let adapter = request_adapter(&instance, None).await;
+        let (device, queue) = request_device(&adapter).await;
+        let sc_desc = surface_configuration(
+            size,
+            wgpu::PresentMode::Fifo,
+            wgpu::CompositeAlphaMode::Opaque,
+        );
+        Self::create(None, &adapter, device, queue, sc_desc, sample_count)
+    }
 
-        surface.configure(&device, &sc_desc);
-        WGPUContext {
+    fn create(
+        surface: Option<wgpu::Surface>,
+        adapter: &wgpu::Adapter,
+        device: wgpu::Device,
+        queue: wgpu::Queue,
+        sc_desc: wgpu::SurfaceConfiguration,
+        sample_count: u32,
+    ) -> WGPUContext {
+        // 检查设备对指定采样数的支持，不支持则关闭多重采样
+        // 未启用适配器相关特性时，设备只保证支持采样数1和4
+        let supported = if device
+            .features()
+            .contains(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES)
+        {
+            adapter
+                .get_texture_format_features(sc_desc.format)
+                .flags
+                .sample_count_supported(sample_count)
+        } else {
+            matches!(sample_count, 1 | 4)
+        };
+        let sample_count = if supported {
+            sample_count
+        } else {
+            log::warn!(
+                "sample count {} is not supported, fall back to 1",
+                sample_count
+            );
+            1
+        };
+        let glob_pipeline = PipelineState::default(&device, sample_count);
+        let blit_pipeline = TextureVertex::create_render_pipeline(&device, 1);
+        let frame_size = Point::new(sc_desc.width, sc_desc.height);
+        let frame = RenderTarget::new(&device, frame_size, sc_desc.format);
+        let globals = GlobalsPool::new(&device, glob_pipeline.globals_layout());
+        let mut context = WGPUContext {
             surface,
             device,
             queue,
             sc_desc,
             glob_pipeline,
-        }
+            sample_count,
+            scale_factor: 1.0,
+            msaa_view: None,
+            frame,
+            backdrop: None,
+            capture_target: None,
+            blit_pipeline,
+            image_cache: ImageCache::default(),
+            globals,
+            layer_pool: Vec::new(),
+        };
+        context.update_msaa_view();
+        context
     }
     // 更新交换缓冲区
     pub fn update_surface_configure<P: Into<Point<u32>>>(&mut self, size: P) {
         let size = size.into();
         self.sc_desc.width = size.x;
         self.sc_desc.height = size.y;
-        self.surface.configure(&self.device, &self.sc_desc);
+        if let Some(surface) = &self.surface {
+            surface.configure(&self.device, &self.sc_desc);
+        }
+        self.update_msaa_view();
+        self.frame = RenderTarget::new(&self.device, size, self.sc_desc.format);
+        self.backdrop = None;
+        self.layer_pool.clear();
+    }
+    /// 按当前帧尺寸重建多重采样渲染目标
+    fn update_msaa_view(&mut self) {
+        self.msaa_view = if self.sample_count > 1 {
+            Some(create_multisampled_view(
+                &self.device,
+                self.get_surface_size(),
+                self.sc_desc.format,
+                self.sample_count,
+            ))
+        } else {
+            None
+        };
+    }
+    /// 注册自定义顶点布局的渲染管道，同一图形类型重复注册时返回错误
+    pub fn register_pipeline<V: VertexLayout>(&mut self) -> Result<(), PipelineError> {
+        self.glob_pipeline.set_pipeline::<V>(&self.device)
+    }
+    /// 写入全局参数，返回绑定组及动态偏移
+    pub(crate) fn push_globals(&mut self, globals: &Globals) -> (Rc<BindGroup>, u32) {
+        self.globals.push(
+            &self.device,
+            &self.queue,
+            self.glob_pipeline.globals_layout(),
+            globals,
+        )
     }
     /// 获取渲染管线
     pub fn get_pipeline(&self, shape_type: ShapeType) -> Option<&RenderPipeline> {
         self.glob_pipeline.get_pipeline(shape_type)
     }
-    /// 获取当前帧尺寸
+    /// 获取多重采样数
+    pub fn get_sample_count(&self) -> u32 {
+        self.sample_count
+    }
+    /// 获取帧缓冲的颜色格式
+    pub fn frame_format(&self) -> wgpu::TextureFormat {
+        self.sc_desc.format
+    }
+    /// 获取当前帧尺寸（物理像素）
     pub fn get_surface_size(&self) -> Point<u32> {
         Point::new(self.sc_desc.width, self.sc_desc.height)
     }
+    /// 设置窗口缩放比例
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.scale_factor = scale_factor;
+    }
+    /// 获取窗口缩放比例
+    pub fn get_scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+    /// 获取当前帧的逻辑尺寸
+    pub fn get_logical_size(&self) -> Point<f32> {
+        let size = self.get_surface_size();
+        Point::new(
+            size.x as f32 / self.scale_factor,
+            size.y as f32 / self.scale_factor,
+        )
+    }
 
     /// 显示图形内容
     pub fn present<C, M>(&mut self, container: &mut C, font_map: &mut GCharMap)
@@ -104,17 +219,121 @@ impl WGPUContext {
         C: ComponentModel<M> + 'static,
         M: 'static + Debug,
     {
-        match self.surface.get_current_texture() {
+        let surface = match &self.surface {
+            Some(surface) => surface,
+            None => {
+                log::warn!("headless context has no surface to present");
+                return;
+            }
+        };
+        match surface.get_current_texture() {
             Err(error) => {
                 log::error!("{}", error);
             }
             Ok(target_view) => {
-                let mut utils = RenderUtil::new(&target_view, self);
+                let mut utils = RenderUtil::new(self);
                 utils.clear_frame(BACKGROUND_COLOR);
                 container.draw(&mut utils, font_map);
+                utils.present_frame(&target_view);
                 let _submission = utils.context.queue.submit(Some(utils.encoder.finish()));
                 target_view.present();
+                self.image_cache.trim();
             }
         }
     }
+
+    /// 将组件绘制到截图专用的离屏渲染目标并读回为图像，可以是整个窗口帧或任意组件
+    /// 不影响窗口帧缓冲中的内容，返回图像尺寸为物理像素尺寸
+    pub fn capture<C, M>(&mut self, container: &C, font_map: &mut GCharMap) -> ImageRaw
+    where
+        C: ComponentModel<M> + ?Sized,
+    {
+        let size = self.get_surface_size();
+        let target = match self.capture_target.take() {
+            Some(target) if target.size == size => target,
+            _ => RenderTarget::new(&self.device, size, self.sc_desc.format),
+        };
+        // 渲染工具绘制到帧缓冲，截图期间以截图目标替换
+        let frame = std::mem::replace(&mut self.frame, target);
+        let mut utils = RenderUtil::new(self);
+        utils.clear_frame(BACKGROUND_COLOR);
+        container.draw(&mut utils, font_map);
+        let _submission = utils.context.queue.submit(Some(utils.encoder.finish()));
+        let target = std::mem::replace(&mut self.frame, frame);
+        let image = target.read_pixels(&self.device, &self.queue);
+        self.capture_target = Some(target);
+        image
+    }
+
+    /// 截取组件在指定区域内的图像，区域为逻辑坐标
+    pub fn capture_region<C, M>(
+        &mut self,
+        container: &C,
+        font_map: &mut GCharMap,
+        region: &Rectangle,
+    ) -> ImageRaw
+    where
+        C: ComponentModel<M> + ?Sized,
+    {
+        let image = self.capture(container, font_map);
+        let scale_factor = self.scale_factor;
+        image.crop(
+            (region.position.x.max(0.0) * scale_factor) as u32,
+            (region.position.y.max(0.0) * scale_factor) as u32,
+            (region.width as f32 * scale_factor).ceil() as u32,
+            (region.height as f32 * scale_factor).ceil() as u32,
+        )
+    }
+}
+
+fn create_instance() -> Instance {
+    Instance::new(wgpu::InstanceDescriptor {
+        backends: wgpu::Backends::all(),
+        dx12_shader_compiler: wgpu::Dx12Compiler::Fxc,
+    })
+}
+
+/// 请求图形适配器，离屏渲染时不需要兼容的渲染面板
+async fn request_adapter(instance: &Instance, surface: Option<&wgpu::Surface>) -> wgpu::Adapter {
+    instance
+        .request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            force_fallback_adapter: false,
+            compatible_surface: surface,
+        })
+        .await
+        .expect("Request adapter")
+}
+
+async fn request_device(adapter: &wgpu::Adapter) -> (wgpu::Device, wgpu::Queue) {
+    // 采样数2、8等需启用适配器相关的纹理格式特性，显卡支持时才请求
+    let features = adapter.features() & wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES;
+    adapter
+        .request_device(
+            &wgpu::DeviceDescriptor {
+                label: None,
+                features,
+                limits: wgpu::Limits::downlevel_defaults().using_resolution(adapter.limits()),
+            },
+            None, // Trace path
+        )
+        .await
+        .unwrap()
+}
+
+/// 交换缓冲区描述符，离屏渲染时只用于记录帧尺寸及颜色格式
+fn surface_configuration(
+    size: Point<u32>,
+    present_mode: wgpu::PresentMode,
+    alpha_mode: wgpu::CompositeAlphaMode,
+) -> wgpu::SurfaceConfiguration {
+    wgpu::SurfaceConfiguration {
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+        format: wgpu::TextureFormat::Bgra8UnormSrgb,
+        width: size.x,
+        height: size.y,
+        present_mode,
+        alpha_mode,
+        view_formats: vec![wgpu::TextureFormat::Bgra8UnormSrgb],
+    }
 }
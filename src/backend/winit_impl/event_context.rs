@@ -1,145 +1,146 @@
+use std::cell::Cell;
 use std::fmt::Debug;
 use std::future::Future;
 use std::path::Path;
+use std::rc::Rc;
+use std::time::Instant;
 
 use futures::channel::mpsc;
 use futures::{task, StreamExt};
+use winit::dpi::PhysicalSize;
 use winit::event::*;
 use winit::event_loop::*;
 use winit::window::*;
 
 use crate::adapter::*;
+use crate::backend::runtime::{MessageSender, Runtime, WindowBackend};
+use crate::backend::winit_impl::event_type_transfer::translate_cursor;
 use crate::event::*;
 use crate::graphic::base::*;
 use crate::instance::Setting;
 use crate::widget::*;
 
 /// 事件上下文
-pub struct WEventContext<M: 'static> {
-    /// 窗口id
-    window: Window,
-    /// 鼠标位置
-    cursor_pos: Point<f32>,
-    /// 窗口事件
-    window_event: Option<GEvent>,
-    /// 自定义事件
-    message: Option<M>,
+pub type WEventContext<M> = Runtime<M, WinitWindow<M>>;
+
+/// winit窗口后端
+pub struct WinitWindow<M: 'static> {
+    /// 窗口
+    pub(crate) window: Window,
     /// 自定义事件广播器
-    message_channel: EventLoopProxy<M>,
+    message_channel: EventLoopProxy<Envelope<M>>,
 }
 
-impl<M: 'static> WEventContext<M> {
-    pub fn new(window: Window, event_loop: &EventLoop<M>) -> WEventContext<M> {
-        WEventContext {
-            window,
-            cursor_pos: Point::new(-1.0, -1.0),
-            window_event: None,
-            message: None,
-            message_channel: event_loop.create_proxy(),
-        }
+impl<M: Send + 'static> MessageSender<M> for EventLoopProxy<Envelope<M>> {
+    fn send(&self, envelope: Envelope<M>) -> Result<(), String> {
+        self.send_event(envelope)
+            .map_err(|_| "event loop closed".to_string())
     }
 }
 
-impl<M> EventContext<M> for WEventContext<M> {
-    /// 更新鼠标坐标
-    fn set_cursor_pos(&mut self, pos: Point<f32>) {
-        self.cursor_pos = pos;
-    }
-
-    fn get_cursor_pos(&self) -> Point<f32> {
-        self.cursor_pos
-    }
-
-    /// 设置鼠标图标
-    fn set_cursor_icon(&mut self, cursor: Cursor) {
-        match cursor {
-            Cursor::Default => self.window.set_cursor_icon(CursorIcon::Default),
-            Cursor::Text => self.window.set_cursor_icon(CursorIcon::Text),
-        }
-    }
-    /// 设置输入框位置
-    fn set_ime_position(&mut self) {
-        self.window.set_ime_position(self.cursor_pos);
-    }
+impl<M: Send + 'static> WindowBackend<M> for WinitWindow<M> {
+    type Sender = EventLoopProxy<Envelope<M>>;
 
-    fn set_event(&mut self, event: GEvent) {
-        self.window_event = Some(event);
+    fn sender(&self) -> Self::Sender {
+        self.message_channel.clone()
     }
 
-    /// 获取当前事件
-    fn get_event(&self) -> GEvent {
-        self.window_event.clone().unwrap()
+    /// winit后端暂不支持自定义图像，自定义图标显示为默认图标
+    fn set_cursor(&mut self, cursor: Cursor) {
+        let icon = translate_cursor(&cursor).unwrap_or_else(|| {
+            log::warn!("custom cursor is not supported by winit backend");
+            CursorIcon::Default
+        });
+        self.window.set_cursor_icon(icon);
     }
 
-    fn get_message(&self) -> Option<&M> {
-        self.message.as_ref()
-    }
-
-    fn set_message(&mut self, message: Option<M>) {
-        self.message = message;
-    }
-    /// 发送自定义事件消息
-    fn send_message(&self, message: M) {
-        self.message_channel.send_event(message).ok();
+    fn set_ime_position(&mut self, position: Point<f32>) {
+        self.window.set_ime_position(position);
     }
 }
 
 /// 初始化窗口
-pub(crate) async fn init<M: 'static + Debug>(setting: Setting) -> DisplayWindow<M> {
+pub(crate) async fn init<M: 'static + Send + Debug>(setting: Setting) -> DisplayWindow<M> {
     log::info!("Initializing the window...");
     let mut builder = WindowBuilder::new();
-    let icon = if setting.icon_path.is_some() {
-        load_icon(Path::new(setting.icon_path.unwrap().as_str()))
-    } else {
-        None
-    };
+    let icon = setting
+        .icon_path
+        .and_then(|icon_path| load_icon(Path::new(icon_path.as_str())));
     builder = builder
         .with_title(setting.title)
         .with_inner_size(winit::dpi::LogicalSize::new(setting.size.x, setting.size.y))
         .with_window_icon(icon);
-    let event_loop = EventLoop::<M>::with_user_event();
+    let event_loop = EventLoopBuilder::<Envelope<M>>::with_user_event().build();
     let window = builder.build(&event_loop).unwrap();
-    let gpu_context = GPUContext::new(&window, window.inner_size().into()).await;
-    let event_context = WEventContext::new(window, &event_loop);
-    let font_map = GCharMap::new(setting.font_path, DEFAULT_FONT_SIZE);
-    let display_window = DisplayWindow {
+    let mut gpu_context =
+        GPUContext::new(&window, window.inner_size().into(), setting.sample_count).await;
+    let scale_factor = window.scale_factor() as f32;
+    let message_channel = event_loop.create_proxy();
+    let event_context = Runtime::new(
+        WinitWindow {
+            window,
+            message_channel,
+        },
+        scale_factor,
+    );
+    let mut font_map = GCharMap::new(setting.font_path, DEFAULT_FONT_SIZE);
+    gpu_context.set_scale_factor(scale_factor);
+    font_map.set_scale_factor(scale_factor);
+    DisplayWindow {
         gpu_context,
         event_loop,
         event_context,
         font_map,
-    };
-    return display_window;
+    }
+}
+
+/// 发送给事件监听器的事件
+enum ListenerEvent<M: 'static> {
+    /// 可转为静态事件的窗口事件
+    Window(Event<'static, Envelope<M>>),
+    /// 缩放比例变更，携带新的缩放比例及窗口物理尺寸
+    /// 原事件持有窗口尺寸的可变引用，无法转为静态事件
+    ScaleFactorChanged {
+        scale_factor: f64,
+        size: PhysicalSize<u32>,
+    },
 }
 
 /// 运行窗口实例
 pub(crate) fn run<C, M>(window: DisplayWindow<M>, container: C)
 where
     C: ComponentModel<M> + 'static,
-    M: 'static + Debug,
+    M: 'static + Clone + Send + Debug,
 {
     let (mut sender, receiver) = mpsc::unbounded();
+    // 监听器设置的下一次唤醒时刻，用于驱动动画及定时器
+    let wake = Rc::new(Cell::new(None));
     let mut instance_listener = Box::pin(event_listener(
         window.gpu_context,
         window.event_context,
         window.font_map,
         container,
         receiver,
+        wake.clone(),
     ));
     let mut context = task::Context::from_waker(task::noop_waker_ref());
     window.event_loop.run(move |event, _, control_flow| {
         if ControlFlow::Exit == *control_flow {
             return;
         }
-        // 封装窗口尺寸变更事件
         let event = match event {
             Event::WindowEvent {
-                event: WindowEvent::ScaleFactorChanged { new_inner_size, .. },
-                window_id,
-            } => Some(Event::WindowEvent {
-                event: WindowEvent::Resized(*new_inner_size),
-                window_id,
+                event:
+                    WindowEvent::ScaleFactorChanged {
+                        scale_factor,
+                        new_inner_size,
+                    },
+                ..
+            } => Some(ListenerEvent::ScaleFactorChanged {
+                scale_factor,
+                size: *new_inner_size,
             }),
-            _ => event.to_static(),
+            _ => event.to_static().map(ListenerEvent::Window),
         };
         // 异步发送到事件监听器
         if let Some(event) = event {
@@ -148,7 +149,10 @@ where
             *control_flow = match poll {
                 task::Poll::Pending => {
                     // println!("--------pending--------");
-                    ControlFlow::Wait
+                    match wake.get() {
+                        Some(instant) => ControlFlow::WaitUntil(instant),
+                        None => ControlFlow::Wait,
+                    }
                 }
                 task::Poll::Ready(_) => {
                     // println!("--------ready--------");
@@ -166,47 +170,103 @@ async fn event_listener<C, M>(
     mut event_context: WEventContext<M>,
     mut font_map: GCharMap,
     mut container: C,
-    mut receiver: mpsc::UnboundedReceiver<winit::event::Event<'static, M>>,
+    mut receiver: mpsc::UnboundedReceiver<ListenerEvent<M>>,
+    wake: Rc<Cell<Option<Instant>>>,
 ) where
     C: ComponentModel<M> + 'static,
-    M: 'static + Debug,
+    M: 'static + Clone + Send + Debug,
 {
+    event_context.start(&mut container);
+    wake.set(event_context.wake());
     while let Some(event) = receiver.next().await {
+        let event = match event {
+            ListenerEvent::Window(event) => event,
+            ListenerEvent::ScaleFactorChanged { scale_factor, size } => {
+                // 同步缩放比例，按新的物理尺寸更新交换缓冲区并重绘
+                rescale(
+                    &mut event_context,
+                    &mut gpu_context,
+                    &mut font_map,
+                    scale_factor as f32,
+                );
+                gpu_context.update_surface_configure(size);
+                gpu_context.present(&mut container, &mut font_map);
+                wake.set(event_context.finish(&mut gpu_context, &container, &mut font_map));
+                continue;
+            }
+        };
+        let window_id = event_context.backend.window.id();
         match event {
-            Event::WindowEvent { event, window_id } if window_id == event_context.window.id() => {
+            Event::WindowEvent {
+                event,
+                window_id: id,
+            } if id == window_id => {
                 // 捕获窗口关闭请求
                 if event == WindowEvent::CloseRequested {
                     break;
                 }
                 match event {
                     WindowEvent::Resized(new_size) => {
-                        // 更新swapChain交换缓冲区
+                        // 同步缩放比例，并更新swapChain交换缓冲区
+                        let scale_factor = event_context.backend.window.scale_factor() as f32;
+                        rescale(
+                            &mut event_context,
+                            &mut gpu_context,
+                            &mut font_map,
+                            scale_factor,
+                        );
                         gpu_context.update_surface_configure(new_size);
                     }
-                    // 储存鼠标位置坐标
+                    // 储存鼠标位置坐标，转换为逻辑坐标
                     WindowEvent::CursorMoved { position, .. } => {
+                        let scale_factor = event_context.backend.window.scale_factor();
+                        let position = position.to_logical::<f64>(scale_factor);
                         event_context.set_cursor_pos(position.into());
                     }
                     _ => {}
                 }
                 // 监听到组件关注事件，决定是否重绘
-                event_context.set_event(event.into());
-                if container.listener(&mut event_context) {
-                    gpu_context.present(&mut container, &mut font_map)
-                }
+                event_context.handle_event(
+                    &mut gpu_context,
+                    &mut container,
+                    &mut font_map,
+                    event.into(),
+                );
             }
-            Event::RedrawRequested(window_id) if window_id == event_context.window.id() => {
+            Event::RedrawRequested(id) if id == window_id => {
                 gpu_context.present(&mut container, &mut font_map)
             }
-            Event::UserEvent(event) => {
-                event_context.set_message(Some(event));
-                println!("{:?}", event_context.get_message());
+            Event::UserEvent(envelope) => {
+                log::debug!("customer event: {:?}", envelope);
+                event_context.handle_message(
+                    &mut gpu_context,
+                    &mut container,
+                    &mut font_map,
+                    Some(envelope),
+                );
+            }
+            // 到达唤醒时刻，发送到期定时器的消息并绘制动画的下一帧
+            Event::NewEvents(StartCause::ResumeTimeReached { .. }) => {
+                event_context.wake_up(&mut gpu_context, &mut container, &mut font_map);
             }
             _ => {}
         }
+        wake.set(event_context.finish(&mut gpu_context, &container, &mut font_map));
     }
 }
 
+/// 同步事件上下文、图形上下文及字体缓冲的缩放比例
+fn rescale<M: Send + 'static>(
+    event_context: &mut WEventContext<M>,
+    gpu_context: &mut GPUContext,
+    font_map: &mut GCharMap,
+    scale_factor: f32,
+) {
+    event_context.set_scale_factor(scale_factor);
+    gpu_context.set_scale_factor(scale_factor);
+    font_map.set_scale_factor(scale_factor);
+}
+
 /// 加载icon
 fn load_icon(path: &Path) -> Option<Icon> {
     let (icon_rgba, icon_width, icon_height) = {
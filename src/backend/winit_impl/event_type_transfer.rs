@@ -1,5 +1,6 @@
 use winit::dpi::PhysicalSize;
 use winit::event::*;
+use winit::window::CursorIcon;
 
 use crate::event::*;
 use crate::graphic::base::Point;
@@ -7,9 +8,9 @@ use crate::graphic::base::Point;
 impl From<Point<f32>> for winit::dpi::Position {
     #[inline]
     fn from(position: Point<f32>) -> winit::dpi::Position {
-        winit::dpi::Position::Physical(winit::dpi::PhysicalPosition {
-            x: position.x as i32,
-            y: position.y as i32,
+        winit::dpi::Position::Logical(winit::dpi::LogicalPosition {
+            x: position.x as f64,
+            y: position.y as f64,
         })
     }
 }
@@ -21,6 +22,13 @@ impl From<winit::dpi::PhysicalPosition<f64>> for Point<f32> {
     }
 }
 
+impl From<winit::dpi::LogicalPosition<f64>> for Point<f32> {
+    #[inline]
+    fn from(position: winit::dpi::LogicalPosition<f64>) -> Point<f32> {
+        Point::new(position.x as f32, position.y as f32)
+    }
+}
+
 impl From<winit::dpi::PhysicalSize<u32>> for Point<u32> {
     fn from(position: PhysicalSize<u32>) -> Self {
         Point::new(position.width, position.height)
@@ -49,6 +57,24 @@ impl From<winit::event::ElementState> for State {
     }
 }
 
+/// 鼠标图标转换，自定义图标没有对应的winit图标
+pub(crate) fn translate_cursor(cursor: &Cursor) -> Option<CursorIcon> {
+    match cursor {
+        Cursor::Default => Some(CursorIcon::Default),
+        Cursor::Text => Some(CursorIcon::Text),
+        Cursor::Pointer => Some(CursorIcon::Hand),
+        Cursor::Move => Some(CursorIcon::Move),
+        Cursor::Crosshair => Some(CursorIcon::Crosshair),
+        Cursor::Wait => Some(CursorIcon::Wait),
+        Cursor::NotAllowed => Some(CursorIcon::NotAllowed),
+        Cursor::ResizeHorizontal => Some(CursorIcon::EwResize),
+        Cursor::ResizeVertical => Some(CursorIcon::NsResize),
+        Cursor::ResizeNeSw => Some(CursorIcon::NeswResize),
+        Cursor::ResizeNwSe => Some(CursorIcon::NwseResize),
+        Cursor::Custom(_) => None,
+    }
+}
+
 /// winit事件转换
 impl From<winit::event::WindowEvent<'_>> for GEvent {
     fn from(winit_event: WindowEvent) -> Self {
@@ -79,6 +105,10 @@ impl From<winit::event::WindowEvent<'_>> for GEvent {
                 event: EventType::ReceivedCharacter(c),
                 state: State::None,
             },
+            WindowEvent::CursorMoved { .. } => GEvent {
+                event: EventType::CursorMoved,
+                state: State::None,
+            },
             _ => GEvent {
                 event: EventType::Other,
                 state: State::None,
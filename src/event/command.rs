@@ -0,0 +1,164 @@
+use std::fmt::Formatter;
+use std::future::Future;
+use std::pin::Pin;
+
+use crate::event::{Timer, TimerId};
+
+/// 异步任务，完成后产生一条消息
+pub type Task<M> = Pin<Box<dyn Future<Output = M> + Send>>;
+
+/// 命令结构体
+/// 由`Instance::update`返回，其中的异步任务在后台线程执行，结果作为消息发送回实例
+pub struct Command<M> {
+    tasks: Vec<Task<M>>,
+    /// 截图保存路径
+    captures: Vec<String>,
+    /// 待注册的定时器，到期消息发送给返回命令的实例
+    timers: Vec<Timer<M>>,
+    /// 待取消的定时器
+    cancelled_timers: Vec<TimerId>,
+}
+
+/// 命令拆分后的各部分，由事件上下文执行
+#[allow(missing_debug_implementations)]
+pub struct CommandParts<M> {
+    /// 异步任务
+    pub tasks: Vec<Task<M>>,
+    /// 截图保存路径
+    pub captures: Vec<String>,
+    /// 待注册的定时器
+    pub timers: Vec<Timer<M>>,
+    /// 待取消的定时器
+    pub cancelled_timers: Vec<TimerId>,
+}
+
+impl<M: Send + 'static> Command<M> {
+    /// 不执行任何任务
+    pub fn none() -> Command<M> {
+        Command {
+            tasks: Vec::new(),
+            captures: Vec::new(),
+            timers: Vec::new(),
+            cancelled_timers: Vec::new(),
+        }
+    }
+
+    /// 执行异步任务，并将结果转换为消息
+    pub fn perform<F, T, MF>(future: F, map: MF) -> Command<M>
+    where
+        F: Future<Output = T> + Send + 'static,
+        MF: FnOnce(T) -> M + Send + 'static,
+    {
+        Command {
+            tasks: vec![Box::pin(async move { map(future.await) })],
+            ..Command::none()
+        }
+    }
+
+    /// 截取当前窗口帧并保存为png，在本次事件处理完、窗口重绘后执行
+    pub fn capture<P: Into<String>>(path: P) -> Command<M> {
+        Command {
+            captures: vec![path.into()],
+            ..Command::none()
+        }
+    }
+
+    /// 注册定时器，到期消息发送给当前实例，可通过`Timer::id`记录id以便之后取消
+    pub fn timer(timer: Timer<M>) -> Command<M> {
+        Command {
+            timers: vec![timer],
+            ..Command::none()
+        }
+    }
+
+    /// 取消定时器
+    pub fn cancel_timer(id: TimerId) -> Command<M> {
+        Command {
+            cancelled_timers: vec![id],
+            ..Command::none()
+        }
+    }
+
+    /// 合并多个命令，其中的任务并发执行
+    pub fn batch<I: IntoIterator<Item = Command<M>>>(commands: I) -> Command<M> {
+        let mut batch = Command::none();
+        for command in commands {
+            batch.tasks.extend(command.tasks);
+            batch.captures.extend(command.captures);
+            batch.timers.extend(command.timers);
+            batch.cancelled_timers.extend(command.cancelled_timers);
+        }
+        batch
+    }
+
+    /// 将任务及定时器产生的消息转换为另一种消息
+    pub fn map<N, F>(self, map: F) -> Command<N>
+    where
+        N: Send + 'static,
+        F: Fn(M) -> N + Clone + Send + 'static,
+    {
+        Command {
+            tasks: self
+                .tasks
+                .into_iter()
+                .map(|task| {
+                    let map = map.clone();
+                    Box::pin(async move {
+                        let message = task.await;
+                        map(message)
+                    }) as Task<N>
+                })
+                .collect(),
+            captures: self.captures,
+            timers: self
+                .timers
+                .into_iter()
+                .map(|timer| timer.map(map.clone()))
+                .collect(),
+            cancelled_timers: self.cancelled_timers,
+        }
+    }
+
+    /// 是否没有任务
+    pub fn is_none(&self) -> bool {
+        self.tasks.is_empty()
+            && self.captures.is_empty()
+            && self.timers.is_empty()
+            && self.cancelled_timers.is_empty()
+    }
+
+    /// 拆分命令，取出所有任务、截图保存路径及定时器
+    pub fn into_parts(self) -> CommandParts<M> {
+        CommandParts {
+            tasks: self.tasks,
+            captures: self.captures,
+            timers: self.timers,
+            cancelled_timers: self.cancelled_timers,
+        }
+    }
+}
+
+impl<M> std::fmt::Debug for Command<M> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Command")
+            .field("tasks", &self.tasks.len())
+            .field("captures", &self.captures)
+            .field("timers", &self.timers.len())
+            .field("cancelled_timers", &self.cancelled_timers)
+            .finish()
+    }
+}
+
+/// 在新线程中执行任务，完成后通过send发送消息
+pub(crate) fn spawn_task<M, S>(task: Task<M>, send: S)
+where
+    M: Send + 'static,
+    S: FnOnce(M) + Send + 'static,
+{
+    let spawned = std::thread::Builder::new()
+        .name("command task".to_string())
+        .spawn(move || send(futures::executor::block_on(task)));
+    if let Err(error) = spawned {
+        log::error!("failed to spawn command task: {}", error);
+    }
+}
@@ -1,4 +1,11 @@
 use std::fmt::*;
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::event::Gesture;
+use crate::graphic::base::{ImageRaw, Point};
 
 /// 控件点击状态结构体
 #[derive(Debug, Clone, PartialOrd, PartialEq)]
@@ -25,6 +32,81 @@ pub enum Cursor {
     Default,
     /// Indicates text that may be selected or edited.
     Text,
+    /// Indicates a link or a clickable element, typically a hand.
+    Pointer,
+    /// Indicates something is to be moved.
+    Move,
+    /// Indicates a precise selection, typically a cross.
+    Crosshair,
+    /// Indicates the program is busy and the user should wait.
+    Wait,
+    /// Indicates the requested action will not be carried out.
+    NotAllowed,
+    /// Indicates a horizontal (left-right) resize, e.g. a vertical splitter.
+    ResizeHorizontal,
+    /// Indicates a vertical (up-down) resize, e.g. a horizontal splitter.
+    ResizeVertical,
+    /// Indicates a resize along the north-east / south-west diagonal.
+    ResizeNeSw,
+    /// Indicates a resize along the north-west / south-east diagonal.
+    ResizeNwSe,
+    /// A cursor drawn from a custom image.
+    /// Only supported by the sdl2 backend; the winit backend shows `Default` instead.
+    Custom(CustomCursor),
+}
+
+impl Cursor {
+    /// 由RGBA图像创建自定义鼠标图标，hotspot为点击位置相对图像左上角的像素坐标
+    ///
+    /// 每次调用都会注册一份新图像，应在创建组件时调用一次并保存返回的图标。
+    /// 仅sdl2后端支持自定义图标；winit后端不支持，会记录警告并显示默认图标
+    pub fn custom(image: ImageRaw, hotspot: Point<u32>) -> Cursor {
+        Cursor::Custom(CustomCursor::new(image, hotspot))
+    }
+}
+
+/// 已注册的自定义鼠标图标图像，以注册序号索引
+static CUSTOM_CURSOR_IMAGES: Mutex<Vec<Arc<ImageRaw>>> = Mutex::new(Vec::new());
+
+/// 自定义鼠标图标
+/// 图像保存在全局注册表中，图标只记录注册序号，因此可以复制，
+/// 比较与哈希也只按序号与hotspot进行
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CustomCursor {
+    id: usize,
+    hotspot_x: u32,
+    hotspot_y: u32,
+}
+
+impl CustomCursor {
+    /// 注册自定义鼠标图标，超出图像的hotspot被限制在图像范围内
+    pub fn new(image: ImageRaw, hotspot: Point<u32>) -> CustomCursor {
+        let hotspot_x = hotspot.x.min(image.width.saturating_sub(1));
+        let hotspot_y = hotspot.y.min(image.height.saturating_sub(1));
+        let mut images = CUSTOM_CURSOR_IMAGES
+            .lock()
+            .expect("custom cursor registry poisoned");
+        images.push(Arc::new(image));
+        CustomCursor {
+            id: images.len() - 1,
+            hotspot_x,
+            hotspot_y,
+        }
+    }
+
+    /// 获取图标图像，图标不是由本进程注册时返回None
+    pub fn image(&self) -> Option<Arc<ImageRaw>> {
+        CUSTOM_CURSOR_IMAGES
+            .lock()
+            .expect("custom cursor registry poisoned")
+            .get(self.id)
+            .cloned()
+    }
+
+    pub fn hotspot(&self) -> Point<u32> {
+        Point::new(self.hotspot_x, self.hotspot_y)
+    }
 }
 
 /// 事件类型枚举
@@ -33,6 +115,10 @@ pub enum EventType {
     Mouse(Mouse),
     KeyBoard(Option<KeyCode>),
     ReceivedCharacter(char),
+    /// 鼠标移动，位置由事件上下文获取
+    CursorMoved,
+    /// 手势，由原始鼠标事件识别得到
+    Gesture(Gesture),
     Other,
 }
 
@@ -44,6 +130,18 @@ pub struct BindEvent<M> {
     pub shortcuts: Option<Vec<KeyCode>>,
 }
 
+/// 实例id，由`Frame`分配，用于将消息发送给指定实例
+pub type InstanceId = u64;
+
+/// 消息信封，记录消息的目标实例
+#[derive(Debug, Clone)]
+pub struct Envelope<M> {
+    /// 目标实例，为None时广播给所有实例
+    pub target: Option<InstanceId>,
+    /// 消息
+    pub message: M,
+}
+
 /// 组件状态结构体，记录绑定的事件、及与事件联动的消息
 #[derive(Debug, Clone)]
 pub struct GEvent {
@@ -51,6 +149,16 @@ pub struct GEvent {
     pub state: State,
 }
 
+impl GEvent {
+    /// 是否为鼠标事件，包括按键、移动及手势
+    pub fn is_pointer(&self) -> bool {
+        matches!(
+            self.event,
+            EventType::Mouse(_) | EventType::CursorMoved | EventType::Gesture(_)
+        )
+    }
+}
+
 impl<M> Default for BindEvent<M> {
     fn default() -> Self {
         BindEvent {
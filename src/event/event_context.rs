@@ -1,24 +1,63 @@
-use crate::event::{Cursor, GEvent};
+use crate::event::{
+    Command, Cursor, GEvent, InstanceId, Subscription, SubscriptionScope, Timer, TimerId,
+};
 use crate::graphic::base::Point;
 
 /// 事件上下文接口
 pub trait EventContext<M> {
     /// 设置鼠标位置
     fn set_cursor_pos(&mut self, pos: Point<f32>);
-    /// 获取鼠标位置
+    /// 获取鼠标位置（逻辑坐标）
     fn get_cursor_pos(&self) -> Point<f32>;
-    /// 设置鼠标图标
+    /// 设置窗口缩放比例
+    fn set_scale_factor(&mut self, scale_factor: f32);
+    /// 获取窗口缩放比例，即物理像素与逻辑像素之比
+    fn get_scale_factor(&self) -> f32;
+    /// 请求鼠标图标，同一鼠标事件中先请求的组件（即最上层的组件）优先，
+    /// 没有组件请求时恢复默认图标
     fn set_cursor_icon(&mut self, cursor: Cursor);
+    /// 声明鼠标悬停于当前组件之上，同一事件中只有第一个声明的组件（即最上层的组件）成功
+    fn claim_hover(&mut self) -> bool;
     /// 设置输入框位置
     fn set_ime_position(&mut self);
     /// 设置事件
     fn set_event(&mut self, event: GEvent);
     /// 获取当前事件
     fn get_event(&self) -> GEvent;
+    /// 标记当前事件已被处理，事件不再传递给其他组件
+    fn consume(&mut self);
+    /// 当前事件是否已被处理
+    fn is_consumed(&self) -> bool;
+    /// 捕获指针，之后的鼠标事件及手势只交给当前组件，直到鼠标松开
+    fn capture_pointer(&mut self);
+    /// 释放指针捕获
+    fn release_pointer(&mut self);
+    /// 指针是否被捕获
+    fn is_pointer_captured(&self) -> bool;
     /// 获取自定义消息
     fn get_message(&self) -> Option<&M>;
     /// 设置自定义消息
     fn set_message(&mut self, message: Option<M>);
+    /// 获取当前消息的目标实例，为None时广播给所有实例
+    fn get_message_target(&self) -> Option<InstanceId>;
+    /// 设置当前处理事件的实例，之后发送的消息、命令、订阅及定时器的消息均以该实例为目标
+    fn set_route(&mut self, target: Option<InstanceId>);
     /// 发送自定义事件消息
     fn send_message(&self, message: M);
+    /// 在后台执行命令中的异步任务，完成后将结果作为消息发送
+    fn spawn(&mut self, command: Command<M>);
+    /// 请求截取当前窗口帧并保存为png，在本次事件处理完、窗口重绘后执行
+    fn request_capture(&mut self, path: String);
+    /// 更新当前实例的订阅，启动新声明的订阅并停止不再声明的订阅
+    fn subscribe(&mut self, subscription: Subscription<M>);
+    /// 更新当前实例中指定作用域的订阅，与实例自身的订阅分开比对，用于嵌入的子实例
+    fn subscribe_scope(&mut self, scope: SubscriptionScope, subscription: Subscription<M>);
+    /// 停止指定实例及其所有作用域的订阅，用于移除实例
+    fn cancel_instance_subscriptions(&mut self, id: InstanceId);
+    /// 添加定时器，到期时将消息发送给实例
+    fn add_timer(&mut self, timer: Timer<M>) -> TimerId;
+    /// 取消定时器
+    fn cancel_timer(&mut self, id: TimerId);
+    /// 取消以指定实例为目标的所有定时器，用于移除实例
+    fn cancel_instance_timers(&mut self, id: InstanceId);
 }
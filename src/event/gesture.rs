@@ -0,0 +1,311 @@
+use std::time::{Duration, Instant};
+
+use crate::event::{EventType, GEvent, Mouse, State};
+use crate::graphic::base::Point;
+
+/// 鼠标按下后移动超过此距离（逻辑像素）视为拖动
+pub const DRAG_THRESHOLD: f32 = 4.0;
+/// 两次单击的最大间隔，不超过时视为双击
+pub const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
+/// 按住不动超过此时长视为长按
+pub const LONG_PRESS_DURATION: Duration = Duration::from_millis(500);
+
+/// 手势枚举，由鼠标左键的原始事件识别得到，坐标均为逻辑坐标
+#[derive(Debug, Copy, Clone, PartialOrd, PartialEq)]
+pub enum Gesture {
+    /// 开始拖动，start为按下位置
+    DragStart { start: Point<f32> },
+    /// 拖动中
+    DragMove {
+        start: Point<f32>,
+        position: Point<f32>,
+    },
+    /// 结束拖动
+    DragEnd {
+        start: Point<f32>,
+        position: Point<f32>,
+    },
+    /// 单击
+    Click(Point<f32>),
+    /// 双击，在第二次单击之后发送
+    DoubleClick(Point<f32>),
+    /// 长按，按住期间发送一次，之后松开不再视为单击
+    LongPress(Point<f32>),
+}
+
+impl Gesture {
+    /// 将手势中的坐标平移，用于转换到组件的局部坐标
+    pub fn translate(self, dx: f32, dy: f32) -> Gesture {
+        let offset = |p: Point<f32>| Point::new(p.x + dx, p.y + dy);
+        match self {
+            Gesture::DragStart { start } => Gesture::DragStart {
+                start: offset(start),
+            },
+            Gesture::DragMove { start, position } => Gesture::DragMove {
+                start: offset(start),
+                position: offset(position),
+            },
+            Gesture::DragEnd { start, position } => Gesture::DragEnd {
+                start: offset(start),
+                position: offset(position),
+            },
+            Gesture::Click(p) => Gesture::Click(offset(p)),
+            Gesture::DoubleClick(p) => Gesture::DoubleClick(offset(p)),
+            Gesture::LongPress(p) => Gesture::LongPress(offset(p)),
+        }
+    }
+}
+
+/// 鼠标按下状态
+#[derive(Debug, Copy, Clone)]
+struct Press {
+    start: Point<f32>,
+    time: Instant,
+    dragging: bool,
+    long_pressed: bool,
+}
+
+/// 手势识别器
+#[derive(Debug)]
+pub struct GestureRecognizer {
+    press: Option<Press>,
+    /// 上一次单击的位置及时刻，用于识别双击
+    last_click: Option<(Point<f32>, Instant)>,
+}
+
+impl GestureRecognizer {
+    pub fn new() -> GestureRecognizer {
+        GestureRecognizer {
+            press: None,
+            last_click: None,
+        }
+    }
+
+    /// 处理原始事件，返回识别出的手势
+    pub fn handle(&mut self, event: &GEvent, cursor: Point<f32>, now: Instant) -> Vec<Gesture> {
+        match (&event.event, &event.state) {
+            (EventType::Mouse(Mouse::Left), State::Pressed) => {
+                self.press = Some(Press {
+                    start: cursor,
+                    time: now,
+                    dragging: false,
+                    long_pressed: false,
+                });
+                Vec::new()
+            }
+            (EventType::CursorMoved, _) => {
+                let press = match &mut self.press {
+                    Some(press) => press,
+                    None => return Vec::new(),
+                };
+                let start = press.start;
+                let position = cursor;
+                if press.dragging {
+                    return vec![Gesture::DragMove { start, position }];
+                }
+                if distance(start, cursor) > DRAG_THRESHOLD {
+                    press.dragging = true;
+                    return vec![
+                        Gesture::DragStart { start },
+                        Gesture::DragMove { start, position },
+                    ];
+                }
+                Vec::new()
+            }
+            (EventType::Mouse(Mouse::Left), State::Released) => {
+                let press = match self.press.take() {
+                    Some(press) => press,
+                    None => return Vec::new(),
+                };
+                if press.dragging {
+                    self.last_click = None;
+                    return vec![Gesture::DragEnd {
+                        start: press.start,
+                        position: cursor,
+                    }];
+                }
+                if press.long_pressed {
+                    self.last_click = None;
+                    return Vec::new();
+                }
+                match self.last_click.take() {
+                    Some((position, time))
+                        if now.saturating_duration_since(time) <= DOUBLE_CLICK_INTERVAL
+                            && distance(position, cursor) <= DRAG_THRESHOLD =>
+                    {
+                        vec![Gesture::Click(cursor), Gesture::DoubleClick(cursor)]
+                    }
+                    _ => {
+                        self.last_click = Some((cursor, now));
+                        vec![Gesture::Click(cursor)]
+                    }
+                }
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// 检查按住时长，到达长按时长时返回长按手势
+    pub fn poll(&mut self, now: Instant) -> Option<Gesture> {
+        let press = self.press.as_mut()?;
+        if press.dragging || press.long_pressed {
+            return None;
+        }
+        if now.saturating_duration_since(press.time) < LONG_PRESS_DURATION {
+            return None;
+        }
+        press.long_pressed = true;
+        Some(Gesture::LongPress(press.start))
+    }
+
+    /// 下一次需要检查长按的时刻
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.press
+            .filter(|press| !press.dragging && !press.long_pressed)
+            .map(|press| press.time + LONG_PRESS_DURATION)
+    }
+}
+
+impl Default for GestureRecognizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 两点间距离
+fn distance(a: Point<f32>, b: Point<f32>) -> f32 {
+    ((a.x - b.x).powi(2) + (a.y - b.y).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn left(state: State) -> GEvent {
+        GEvent {
+            event: EventType::Mouse(Mouse::Left),
+            state,
+        }
+    }
+
+    fn moved() -> GEvent {
+        GEvent {
+            event: EventType::CursorMoved,
+            state: State::None,
+        }
+    }
+
+    fn click(
+        recognizer: &mut GestureRecognizer,
+        position: Point<f32>,
+        now: Instant,
+    ) -> Vec<Gesture> {
+        recognizer.handle(&left(State::Pressed), position, now);
+        recognizer.handle(&left(State::Released), position, now)
+    }
+
+    #[test]
+    fn small_move_is_not_drag() {
+        let now = Instant::now();
+        let start = Point::new(10.0, 10.0);
+        let mut recognizer = GestureRecognizer::new();
+        recognizer.handle(&left(State::Pressed), start, now);
+        let inside = Point::new(10.0 + DRAG_THRESHOLD, 10.0);
+        assert!(recognizer.handle(&moved(), inside, now).is_empty());
+        assert_eq!(
+            recognizer.handle(&left(State::Released), inside, now),
+            vec![Gesture::Click(inside)]
+        );
+    }
+
+    #[test]
+    fn move_beyond_threshold_starts_drag() {
+        let now = Instant::now();
+        let start = Point::new(10.0, 10.0);
+        let position = Point::new(20.0, 10.0);
+        let end = Point::new(30.0, 10.0);
+        let mut recognizer = GestureRecognizer::new();
+        recognizer.handle(&left(State::Pressed), start, now);
+        assert_eq!(
+            recognizer.handle(&moved(), position, now),
+            vec![
+                Gesture::DragStart { start },
+                Gesture::DragMove { start, position },
+            ]
+        );
+        assert_eq!(
+            recognizer.handle(&moved(), end, now),
+            vec![Gesture::DragMove {
+                start,
+                position: end
+            }]
+        );
+        assert_eq!(
+            recognizer.handle(&left(State::Released), end, now),
+            vec![Gesture::DragEnd {
+                start,
+                position: end
+            }]
+        );
+        assert_eq!(recognizer.next_deadline(), None);
+    }
+
+    #[test]
+    fn second_click_within_interval_is_double_click() {
+        let now = Instant::now();
+        let position = Point::new(5.0, 5.0);
+        let mut recognizer = GestureRecognizer::new();
+        assert_eq!(
+            click(&mut recognizer, position, now),
+            vec![Gesture::Click(position)]
+        );
+        assert_eq!(
+            click(&mut recognizer, position, now + DOUBLE_CLICK_INTERVAL),
+            vec![Gesture::Click(position), Gesture::DoubleClick(position)]
+        );
+        // 双击后重新计数，第三次单击不再是双击
+        assert_eq!(
+            click(&mut recognizer, position, now + DOUBLE_CLICK_INTERVAL),
+            vec![Gesture::Click(position)]
+        );
+    }
+
+    #[test]
+    fn second_click_after_interval_is_single_click() {
+        let now = Instant::now();
+        let position = Point::new(5.0, 5.0);
+        let mut recognizer = GestureRecognizer::new();
+        click(&mut recognizer, position, now);
+        let later = now + DOUBLE_CLICK_INTERVAL + Duration::from_millis(1);
+        assert_eq!(
+            click(&mut recognizer, position, later),
+            vec![Gesture::Click(position)]
+        );
+    }
+
+    #[test]
+    fn long_press_suppresses_click() {
+        let now = Instant::now();
+        let position = Point::new(5.0, 5.0);
+        let mut recognizer = GestureRecognizer::new();
+        recognizer.handle(&left(State::Pressed), position, now);
+        assert_eq!(recognizer.next_deadline(), Some(now + LONG_PRESS_DURATION));
+        assert_eq!(recognizer.poll(now), None);
+        assert_eq!(
+            recognizer.poll(now + LONG_PRESS_DURATION),
+            Some(Gesture::LongPress(position))
+        );
+        assert_eq!(recognizer.next_deadline(), None);
+        assert_eq!(recognizer.poll(now + LONG_PRESS_DURATION * 2), None);
+        assert!(recognizer
+            .handle(&left(State::Released), position, now + LONG_PRESS_DURATION)
+            .is_empty());
+    }
+
+    #[test]
+    fn no_deadline_without_press() {
+        let mut recognizer = GestureRecognizer::default();
+        assert_eq!(recognizer.next_deadline(), None);
+        assert_eq!(recognizer.poll(Instant::now()), None);
+    }
+}
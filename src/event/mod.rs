@@ -1,7 +1,20 @@
+pub use command::*;
 pub use event::*;
 pub use event_context::*;
+pub use gesture::*;
+pub use subscription::*;
+pub use timer::*;
 
+/// 异步命令
+mod command;
 /// 事件相关结构体
+#[allow(clippy::module_inception)]
 mod event;
 /// 事件上下文trait
 mod event_context;
+/// 手势识别
+mod gesture;
+/// 订阅
+mod subscription;
+/// 定时器
+mod timer;
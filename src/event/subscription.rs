@@ -0,0 +1,328 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fmt::Formatter;
+use std::hash::{Hash, Hasher};
+use std::pin::Pin;
+
+use futures::stream::{AbortHandle, Abortable};
+use futures::{Stream, StreamExt};
+
+use crate::event::InstanceId;
+
+/// 订阅产生的消息流
+pub type SubscriptionStream<M> = Pin<Box<dyn Stream<Item = M> + Send>>;
+
+/// 订阅项，id相同的订阅视为同一个
+struct Recipe<M> {
+    id: u64,
+    build: Box<dyn FnOnce() -> SubscriptionStream<M>>,
+}
+
+/// 订阅结构体
+/// 由`Instance::subscription`声明，运行时保持订阅的消息流运行，每次状态更新后重新比对：
+/// 新出现的订阅被启动，不再声明的订阅被停止，id不变的订阅继续运行
+pub struct Subscription<M> {
+    recipes: Vec<Recipe<M>>,
+}
+
+impl<M: Send + 'static> Subscription<M> {
+    /// 无订阅
+    pub fn none() -> Subscription<M> {
+        Subscription {
+            recipes: Vec::new(),
+        }
+    }
+
+    /// 创建订阅，build仅在订阅启动时调用一次，其返回的流的每一项都作为消息发送给实例
+    pub fn run<I, F, S>(id: I, build: F) -> Subscription<M>
+    where
+        I: Hash,
+        F: FnOnce() -> S + 'static,
+        S: Stream<Item = M> + Send + 'static,
+    {
+        let mut hasher = DefaultHasher::new();
+        id.hash(&mut hasher);
+        Subscription {
+            recipes: vec![Recipe {
+                id: hasher.finish(),
+                build: Box::new(move || build().boxed()),
+            }],
+        }
+    }
+
+    /// 合并多个订阅
+    pub fn batch<I: IntoIterator<Item = Subscription<M>>>(subscriptions: I) -> Subscription<M> {
+        Subscription {
+            recipes: subscriptions
+                .into_iter()
+                .flat_map(|subscription| subscription.recipes)
+                .collect(),
+        }
+    }
+
+    /// 将订阅产生的消息转换为另一种消息
+    pub fn map<N, F>(self, map: F) -> Subscription<N>
+    where
+        N: Send + 'static,
+        F: Fn(M) -> N + Clone + Send + 'static,
+    {
+        Subscription {
+            recipes: self
+                .recipes
+                .into_iter()
+                .map(|recipe| {
+                    let build = recipe.build;
+                    let map = map.clone();
+                    Recipe {
+                        id: recipe.id,
+                        build: Box::new(move || build().map(map).boxed())
+                            as Box<dyn FnOnce() -> SubscriptionStream<N>>,
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// 是否没有订阅
+    pub fn is_none(&self) -> bool {
+        self.recipes.is_empty()
+    }
+}
+
+impl<M> std::fmt::Debug for Subscription<M> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Subscription")
+            .field(
+                "ids",
+                &self
+                    .recipes
+                    .iter()
+                    .map(|recipe| recipe.id)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+/// 订阅作用域，区分同一实例中由嵌入的子实例声明的订阅
+pub type SubscriptionScope = u64;
+
+/// 正在运行的订阅记录，按订阅所属的实例及作用域分组
+#[derive(Debug, Default)]
+pub struct SubscriptionTracker {
+    running: HashMap<(Option<InstanceId>, Option<SubscriptionScope>), HashMap<u64, AbortHandle>>,
+}
+
+impl SubscriptionTracker {
+    pub fn new() -> SubscriptionTracker {
+        SubscriptionTracker {
+            running: HashMap::new(),
+        }
+    }
+
+    /// 与实例当前运行的订阅比对，停止不再声明的订阅，返回需要启动的新订阅的消息流
+    pub fn update<M: Send + 'static>(
+        &mut self,
+        owner: Option<InstanceId>,
+        subscription: Subscription<M>,
+    ) -> Vec<SubscriptionStream<M>> {
+        self.update_scope(owner, None, subscription)
+    }
+
+    /// 与实例中指定作用域当前运行的订阅比对，各作用域的订阅互不影响
+    /// 作用域为None时即实例自身的订阅
+    pub fn update_scope<M: Send + 'static>(
+        &mut self,
+        owner: Option<InstanceId>,
+        scope: Option<SubscriptionScope>,
+        subscription: Subscription<M>,
+    ) -> Vec<SubscriptionStream<M>> {
+        let owner = (owner, scope);
+        let mut previous = self.running.remove(&owner).unwrap_or_default();
+        let mut running = HashMap::with_capacity(subscription.recipes.len());
+        let mut started = Vec::new();
+        for recipe in subscription.recipes {
+            if running.contains_key(&recipe.id) {
+                log::warn!("duplicate subscription id: {}", recipe.id);
+                continue;
+            }
+            match previous.remove(&recipe.id) {
+                Some(handle) => {
+                    running.insert(recipe.id, handle);
+                }
+                None => {
+                    let (handle, registration) = AbortHandle::new_pair();
+                    started.push(Abortable::new((recipe.build)(), registration).boxed());
+                    running.insert(recipe.id, handle);
+                }
+            }
+        }
+        for (_, handle) in previous {
+            handle.abort();
+        }
+        if !running.is_empty() {
+            self.running.insert(owner, running);
+        }
+        started
+    }
+
+    /// 停止实例及其所有作用域的订阅，返回停止的数量
+    pub fn remove_instance(&mut self, owner: InstanceId) -> usize {
+        let mut removed = 0;
+        self.running.retain(|(instance, _), running| {
+            if *instance != Some(owner) {
+                return true;
+            }
+            for (_, handle) in running.drain() {
+                handle.abort();
+                removed += 1;
+            }
+            false
+        });
+        removed
+    }
+}
+
+impl Drop for SubscriptionTracker {
+    fn drop(&mut self) {
+        for (_, handle) in self.running.drain().flat_map(|(_, running)| running) {
+            handle.abort();
+        }
+    }
+}
+
+/// 在新线程中运行订阅的消息流，流中的每一项通过send发送
+pub(crate) fn spawn_stream<M, S>(stream: SubscriptionStream<M>, mut send: S)
+where
+    M: Send + 'static,
+    S: FnMut(M) + Send + 'static,
+{
+    let spawned = std::thread::Builder::new()
+        .name("subscription".to_string())
+        .spawn(move || {
+            futures::executor::block_on(stream.for_each(|message| {
+                send(message);
+                futures::future::ready(())
+            }))
+        });
+    if let Err(error) = spawned {
+        log::error!("failed to spawn subscription: {}", error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use futures::{stream, FutureExt};
+
+    use super::*;
+
+    /// 永不结束的订阅
+    fn pending(id: &'static str) -> Subscription<u32> {
+        Subscription::run(id, stream::pending::<u32>)
+    }
+
+    /// 订阅的流是否已被停止
+    fn is_aborted(stream: &mut SubscriptionStream<u32>) -> bool {
+        stream.next().now_or_never() == Some(None)
+    }
+
+    #[test]
+    fn redeclared_subscription_keeps_running() {
+        let mut tracker = SubscriptionTracker::new();
+        let mut started = tracker.update(None, pending("a"));
+        assert_eq!(started.len(), 1);
+        assert!(tracker.update(None, pending("a")).is_empty());
+        assert!(!is_aborted(&mut started[0]));
+    }
+
+    #[test]
+    fn undeclared_subscription_is_aborted() {
+        let mut tracker = SubscriptionTracker::new();
+        let mut started = tracker.update(None, Subscription::batch([pending("a"), pending("b")]));
+        assert_eq!(started.len(), 2);
+        let restarted = tracker.update(None, pending("b"));
+        assert!(restarted.is_empty());
+        assert!(is_aborted(&mut started[0]));
+        assert!(!is_aborted(&mut started[1]));
+        tracker.update(None, Subscription::<u32>::none());
+        assert!(is_aborted(&mut started[1]));
+    }
+
+    #[test]
+    fn duplicate_ids_start_once() {
+        let builds = Rc::new(Cell::new(0));
+        let subscription = |builds: Rc<Cell<u32>>| {
+            Subscription::run("a", move || {
+                builds.set(builds.get() + 1);
+                stream::pending::<u32>()
+            })
+        };
+        let mut tracker = SubscriptionTracker::new();
+        let started = tracker.update(
+            None,
+            Subscription::batch([subscription(builds.clone()), subscription(builds.clone())]),
+        );
+        assert_eq!(started.len(), 1);
+        assert_eq!(builds.get(), 1);
+    }
+
+    #[test]
+    fn owners_are_tracked_separately() {
+        let mut tracker = SubscriptionTracker::new();
+        let mut first = tracker.update(Some(1), pending("a"));
+        let mut second = tracker.update(Some(2), pending("a"));
+        assert_eq!(second.len(), 1);
+        tracker.update(Some(1), Subscription::<u32>::none());
+        assert!(is_aborted(&mut first[0]));
+        assert!(!is_aborted(&mut second[0]));
+    }
+
+    #[test]
+    fn scopes_are_tracked_separately() {
+        let mut tracker = SubscriptionTracker::new();
+        let mut own = tracker.update(Some(1), pending("a"));
+        let mut child = tracker.update_scope(Some(1), Some(7), pending("a"));
+        assert_eq!(child.len(), 1);
+        // 重新声明实例自身的订阅不影响子实例的订阅
+        assert!(tracker.update(Some(1), pending("a")).is_empty());
+        assert!(!is_aborted(&mut child[0]));
+        tracker.update(Some(1), Subscription::<u32>::none());
+        assert!(is_aborted(&mut own[0]));
+        assert!(!is_aborted(&mut child[0]));
+    }
+
+    #[test]
+    fn removing_instance_aborts_all_scopes() {
+        let mut tracker = SubscriptionTracker::new();
+        let mut own = tracker.update(Some(1), pending("a"));
+        let mut child = tracker.update_scope(Some(1), Some(7), pending("b"));
+        let mut other = tracker.update(Some(2), pending("a"));
+        assert_eq!(tracker.remove_instance(1), 2);
+        assert!(is_aborted(&mut own[0]));
+        assert!(is_aborted(&mut child[0]));
+        assert!(!is_aborted(&mut other[0]));
+    }
+
+    #[test]
+    fn dropping_tracker_aborts_all() {
+        let mut tracker = SubscriptionTracker::new();
+        let mut started = tracker.update(Some(1), pending("a"));
+        drop(tracker);
+        assert!(is_aborted(&mut started[0]));
+    }
+
+    #[test]
+    fn mapped_subscription_keeps_id() {
+        let mut tracker = SubscriptionTracker::new();
+        let subscription = Subscription::run("a", || stream::iter(vec![1, 2]));
+        let mut started = tracker.update(None, subscription.map(|n: u32| n * 10));
+        assert_eq!(
+            futures::executor::block_on(started.remove(0).collect::<Vec<_>>()),
+            vec![10, 20]
+        );
+        assert!(tracker.update(None, pending("a")).is_empty());
+    }
+}
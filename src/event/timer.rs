@@ -0,0 +1,272 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::event::{Envelope, InstanceId};
+
+/// 定时器id，用于取消定时器
+pub type TimerId = u64;
+
+/// 分配新的定时器id，全局唯一
+fn next_timer_id() -> TimerId {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// 定时器结构体
+/// 到达指定时刻后将消息发送给实例，创建时即分配id，可在注册前记录以便之后取消
+#[derive(Debug, Clone)]
+pub struct Timer<M> {
+    /// 定时器id
+    id: TimerId,
+    /// 到期时发送的消息
+    message: M,
+    /// 下一次到期时刻
+    deadline: Instant,
+    /// 重复间隔，为None时只触发一次
+    interval: Option<Duration>,
+}
+
+impl<M> Timer<M> {
+    /// 延迟指定时长后触发一次
+    pub fn after(delay: Duration, message: M) -> Timer<M> {
+        Timer {
+            id: next_timer_id(),
+            message,
+            deadline: Instant::now() + delay,
+            interval: None,
+        }
+    }
+
+    /// 在指定时刻触发一次
+    pub fn at(deadline: Instant, message: M) -> Timer<M> {
+        Timer {
+            id: next_timer_id(),
+            message,
+            deadline,
+            interval: None,
+        }
+    }
+
+    /// 每隔指定时长触发一次，首次在一个间隔后触发
+    pub fn every(interval: Duration, message: M) -> Timer<M> {
+        Timer {
+            id: next_timer_id(),
+            message,
+            deadline: Instant::now() + interval,
+            interval: Some(interval),
+        }
+    }
+
+    /// 获取定时器id
+    pub fn id(&self) -> TimerId {
+        self.id
+    }
+
+    /// 获取下一次到期时刻
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// 将到期时发送的消息转换为另一种消息
+    pub fn map<N, F: FnOnce(M) -> N>(self, map: F) -> Timer<N> {
+        Timer {
+            id: self.id,
+            message: map(self.message),
+            deadline: self.deadline,
+            interval: self.interval,
+        }
+    }
+}
+
+/// 定时器队列
+#[derive(Debug)]
+pub struct TimerQueue<M> {
+    timers: Vec<(Option<InstanceId>, Timer<M>)>,
+}
+
+impl<M> TimerQueue<M> {
+    pub fn new() -> TimerQueue<M> {
+        TimerQueue { timers: Vec::new() }
+    }
+
+    /// 添加定时器，返回定时器id，target为到期消息的目标实例
+    pub fn add(&mut self, timer: Timer<M>, target: Option<InstanceId>) -> TimerId {
+        let id = timer.id;
+        self.timers.push((target, timer));
+        id
+    }
+
+    /// 取消定时器，定时器不存在时返回false
+    pub fn cancel(&mut self, id: TimerId) -> bool {
+        let count = self.timers.len();
+        self.timers.retain(|(_, timer)| timer.id != id);
+        self.timers.len() != count
+    }
+
+    /// 取消以指定实例为目标的所有定时器，返回取消的数量
+    pub fn cancel_target(&mut self, target: InstanceId) -> usize {
+        let count = self.timers.len();
+        self.timers
+            .retain(|(timer_target, _)| *timer_target != Some(target));
+        count - self.timers.len()
+    }
+
+    /// 获取最近的到期时刻
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.timers.iter().map(|(_, timer)| timer.deadline).min()
+    }
+}
+
+impl<M: Clone> TimerQueue<M> {
+    /// 取出已到期定时器的消息，按到期时刻排序
+    /// 重复定时器推进到下一个未到期的时刻，错过的周期不会补发
+    pub fn take_due(&mut self, now: Instant) -> Vec<Envelope<M>> {
+        let mut due: Vec<(Instant, Envelope<M>)> = Vec::new();
+        self.timers.retain_mut(|(target, timer)| {
+            if timer.deadline > now {
+                return true;
+            }
+            let envelope = Envelope {
+                target: *target,
+                message: timer.message.clone(),
+            };
+            due.push((timer.deadline, envelope));
+            match timer.interval {
+                Some(interval) if !interval.is_zero() => {
+                    while timer.deadline <= now {
+                        timer.deadline += interval;
+                    }
+                    true
+                }
+                _ => false,
+            }
+        });
+        due.sort_by_key(|(deadline, _)| *deadline);
+        due.into_iter().map(|(_, envelope)| envelope).collect()
+    }
+}
+
+impl<M> Default for TimerQueue<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 取两个可选时刻中较早的一个
+pub(crate) fn earliest(a: Option<Instant>, b: Option<Instant>) -> Option<Instant> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: Duration = Duration::from_secs(1);
+
+    fn messages(envelopes: Vec<Envelope<&'static str>>) -> Vec<&'static str> {
+        envelopes
+            .into_iter()
+            .map(|envelope| envelope.message)
+            .collect()
+    }
+
+    fn interval(start: Instant, interval: Duration, message: &'static str) -> Timer<&'static str> {
+        Timer {
+            id: next_timer_id(),
+            message,
+            deadline: start + interval,
+            interval: Some(interval),
+        }
+    }
+
+    #[test]
+    fn one_shot_fires_once_at_deadline() {
+        let start = Instant::now();
+        let mut queue = TimerQueue::new();
+        queue.add(Timer::at(start + SECOND, "once"), Some(3));
+        assert!(queue.take_due(start).is_empty());
+        let due = queue.take_due(start + SECOND);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].target, Some(3));
+        assert_eq!(due[0].message, "once");
+        assert!(queue.take_due(start + SECOND * 10).is_empty());
+        assert_eq!(queue.next_deadline(), None);
+    }
+
+    #[test]
+    fn due_messages_are_sorted_by_deadline() {
+        let start = Instant::now();
+        let mut queue = TimerQueue::new();
+        queue.add(Timer::at(start + SECOND * 2, "late"), None);
+        queue.add(Timer::at(start + SECOND, "early"), None);
+        queue.add(Timer::at(start + SECOND * 5, "pending"), None);
+        assert_eq!(
+            messages(queue.take_due(start + SECOND * 3)),
+            vec!["early", "late"]
+        );
+        assert_eq!(queue.next_deadline(), Some(start + SECOND * 5));
+    }
+
+    #[test]
+    fn interval_skips_missed_periods() {
+        let start = Instant::now();
+        let mut queue = TimerQueue::new();
+        queue.add(interval(start, SECOND, "tick"), None);
+        // 错过三个周期只发送一次消息
+        let late = start + SECOND * 3 + SECOND / 2;
+        assert_eq!(messages(queue.take_due(late)), vec!["tick"]);
+        assert_eq!(queue.next_deadline(), Some(start + SECOND * 4));
+        assert!(queue.take_due(late).is_empty());
+        assert_eq!(messages(queue.take_due(start + SECOND * 4)), vec!["tick"]);
+    }
+
+    #[test]
+    fn zero_interval_fires_once() {
+        let start = Instant::now();
+        let mut queue = TimerQueue::new();
+        queue.add(interval(start, Duration::ZERO, "zero"), None);
+        assert_eq!(messages(queue.take_due(start)), vec!["zero"]);
+        assert_eq!(queue.next_deadline(), None);
+    }
+
+    #[test]
+    fn cancelled_timer_does_not_fire() {
+        let start = Instant::now();
+        let mut queue = TimerQueue::new();
+        let id = queue.add(interval(start, SECOND, "tick"), None);
+        assert!(queue.cancel(id));
+        assert!(!queue.cancel(id));
+        assert!(queue.take_due(start + SECOND * 2).is_empty());
+    }
+
+    #[test]
+    fn timer_can_be_cancelled_by_its_own_id() {
+        let start = Instant::now();
+        let mut queue = TimerQueue::new();
+        let timer = interval(start, SECOND, "tick").map(|message| message.len());
+        let id = timer.id();
+        assert_ne!(id, Timer::at(start, 0).id());
+        assert_eq!(queue.add(timer, None), id);
+        assert!(queue.cancel(id));
+        assert!(queue.take_due(start + SECOND).is_empty());
+    }
+
+    #[test]
+    fn cancel_target_keeps_other_instances() {
+        let start = Instant::now();
+        let mut queue = TimerQueue::new();
+        queue.add(interval(start, SECOND, "first"), Some(1));
+        queue.add(Timer::at(start + SECOND, "first once"), Some(1));
+        queue.add(interval(start, SECOND, "second"), Some(2));
+        queue.add(interval(start, SECOND, "broadcast"), None);
+        assert_eq!(queue.cancel_target(1), 2);
+        assert_eq!(queue.cancel_target(1), 0);
+        assert_eq!(
+            messages(queue.take_due(start + SECOND)),
+            vec!["second", "broadcast"]
+        );
+    }
+}
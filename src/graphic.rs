@@ -1,3 +1,5 @@
+/// 定义补间动画及缓动曲线
+pub mod animation;
 /// 定义具体图形及色彩等渲染所需的结构体
 pub mod base;
 /// 定义渲染接口
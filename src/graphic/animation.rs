@@ -0,0 +1,143 @@
+use std::time::{Duration, Instant};
+
+use crate::graphic::base::*;
+
+/// 动画进行时的重绘间隔，约60帧每秒
+pub const ANIMATION_FRAME: Duration = Duration::from_millis(16);
+
+/// 计算事件循环下一次唤醒重绘的时刻
+/// 有动画时保留尚未到达的唤醒时刻，避免连续事件不断推迟重绘
+pub(crate) fn next_frame(animating: bool, wake: Option<Instant>) -> Option<Instant> {
+    if !animating {
+        return None;
+    }
+    let now = Instant::now();
+    match wake {
+        Some(wake) if wake > now => Some(wake),
+        _ => Some(now + ANIMATION_FRAME),
+    }
+}
+
+/// 缓动曲线枚举
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Easing {
+    /// 匀速
+    Linear,
+    /// 先慢后快
+    EaseIn,
+    /// 先快后慢
+    EaseOut,
+    /// 两端慢中间快
+    EaseInOut,
+}
+
+impl Easing {
+    /// 将线性进度(0.0~1.0)映射为缓动后的进度
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+        }
+    }
+}
+
+/// 线性插值trait
+pub trait Lerp {
+    /// 在self和to之间插值，t为0.0时返回self，为1.0时返回to
+    fn lerp(&self, to: &Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        self + (to - self) * t
+    }
+}
+
+impl Lerp for RGBA {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        RGBA(
+            self.0.lerp(&to.0, t),
+            self.1.lerp(&to.1, t),
+            self.2.lerp(&to.2, t),
+            self.3.lerp(&to.3, t),
+        )
+    }
+}
+
+impl Lerp for Point<f32> {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        Point::new(self.x.lerp(&to.x, t), self.y.lerp(&to.y, t))
+    }
+}
+
+impl Lerp for Rectangle {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        let position = self.position.lerp(&to.position, t);
+        Rectangle::new(
+            position.x,
+            position.y,
+            (self.width as f32).lerp(&(to.width as f32), t).round() as u32,
+            (self.height as f32).lerp(&(to.height as f32), t).round() as u32,
+        )
+    }
+}
+
+/// 补间动画结构体，在给定时长内按缓动曲线由起始值过渡到目标值
+#[derive(Debug, Copy, Clone)]
+pub struct Tween<T> {
+    from: T,
+    to: T,
+    start: Instant,
+    duration: Duration,
+    easing: Easing,
+}
+
+impl<T: Lerp + Copy> Tween<T> {
+    /// 创建补间动画，从当前时刻开始
+    pub fn new(from: T, to: T, duration: Duration, easing: Easing) -> Tween<T> {
+        Tween {
+            from,
+            to,
+            start: Instant::now(),
+            duration,
+            easing,
+        }
+    }
+
+    /// 获取指定时刻的值
+    pub fn value_at(&self, now: Instant) -> T {
+        if self.duration.is_zero() {
+            return self.to;
+        }
+        let elapsed = now.saturating_duration_since(self.start);
+        let t = elapsed.as_secs_f32() / self.duration.as_secs_f32();
+        if t >= 1.0 {
+            return self.to;
+        }
+        self.from.lerp(&self.to, self.easing.apply(t))
+    }
+
+    /// 获取当前时刻的值
+    pub fn value(&self) -> T {
+        self.value_at(Instant::now())
+    }
+
+    /// 获取目标值
+    pub fn target(&self) -> T {
+        self.to
+    }
+
+    /// 动画是否已结束
+    pub fn is_finished(&self) -> bool {
+        self.start.elapsed() >= self.duration
+    }
+}
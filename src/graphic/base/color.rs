@@ -1,6 +1,8 @@
+use crate::graphic::base::Point;
+
 /// 颜色结构体
 #[repr(C)]
-#[derive(Copy, Default, Clone, Debug)]
+#[derive(Copy, Default, Clone, Debug, PartialEq)]
 pub struct RGBA(pub f32, pub f32, pub f32, pub f32);
 
 pub const ALPHA: RGBA = RGBA(0.0, 0.0, 0.0, 0.0);
@@ -26,3 +28,160 @@ impl RGBA {
         [self.0, self.1, self.2, self.3]
     }
 }
+
+/// 渐变最多支持的色标数
+pub const MAX_GRADIENT_STOPS: usize = 4;
+
+/// 渐变色标
+#[derive(Copy, Default, Clone, Debug, PartialEq)]
+pub struct GradientStop {
+    /// 色标位置，范围0.0~1.0
+    pub offset: f32,
+    /// 色标颜色
+    pub color: RGBA,
+}
+
+/// 渐变类型枚举
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GradientKind {
+    /// 线性渐变，角度为度数，0度从左到右，顺时针增加
+    Linear { angle: f32 },
+    /// 径向渐变，圆心和半径均相对于图形外接矩形，
+    /// 圆心(0.5, 0.5)为外接矩形中心，半径1.0为外接矩形的长边
+    Radial { center: Point<f32>, radius: f32 },
+}
+
+/// 渐变结构体
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Gradient {
+    /// 渐变类型
+    pub kind: GradientKind,
+    /// 色标，按位置升序排列
+    stops: [GradientStop; MAX_GRADIENT_STOPS],
+    /// 色标数量
+    stop_count: usize,
+}
+
+/// 填充颜色枚举
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Paint {
+    /// 纯色
+    Solid(RGBA),
+    /// 渐变色
+    Gradient(Gradient),
+}
+
+/// 渐变参数错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum GradientError {
+    /// 没有色标
+    NoStops,
+    /// 色标数超过MAX_GRADIENT_STOPS
+    TooManyStops(usize),
+}
+
+impl std::fmt::Display for GradientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GradientError::NoStops => write!(f, "gradient needs at least one stop"),
+            GradientError::TooManyStops(count) => write!(
+                f,
+                "gradient supports at most {} stops, got {}",
+                MAX_GRADIENT_STOPS, count
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GradientError {}
+
+impl Gradient {
+    /// 创建线性渐变
+    /// 参数：角度（度），色标（位置，颜色），色标数为1~MAX_GRADIENT_STOPS个
+    pub fn linear(angle: f32, stops: &[(f32, RGBA)]) -> Result<Gradient, GradientError> {
+        Self::new(GradientKind::Linear { angle }, stops)
+    }
+
+    /// 创建径向渐变
+    /// 参数：相对圆心，相对半径，色标（位置，颜色），色标数为1~MAX_GRADIENT_STOPS个
+    pub fn radial(
+        center: Point<f32>,
+        radius: f32,
+        stops: &[(f32, RGBA)],
+    ) -> Result<Gradient, GradientError> {
+        Self::new(GradientKind::Radial { center, radius }, stops)
+    }
+
+    fn new(kind: GradientKind, stops: &[(f32, RGBA)]) -> Result<Gradient, GradientError> {
+        if stops.is_empty() {
+            return Err(GradientError::NoStops);
+        }
+        if stops.len() > MAX_GRADIENT_STOPS {
+            return Err(GradientError::TooManyStops(stops.len()));
+        }
+        let mut sorted: Vec<GradientStop> = stops
+            .iter()
+            .map(|(offset, color)| GradientStop {
+                offset: offset.clamp(0.0, 1.0),
+                color: *color,
+            })
+            .collect();
+        sorted.sort_by(|a, b| a.offset.total_cmp(&b.offset));
+        let mut gradient = Gradient {
+            kind,
+            stops: [GradientStop::default(); MAX_GRADIENT_STOPS],
+            stop_count: sorted.len(),
+        };
+        gradient.stops[..sorted.len()].copy_from_slice(&sorted);
+        Ok(gradient)
+    }
+
+    /// 将所有色标颜色按from到to的差值平移，用于按交互状态的颜色变化为渐变着色
+    pub fn shift(mut self, from: RGBA, to: RGBA) -> Gradient {
+        let offset = |value: f32, from: f32, to: f32| (value + to - from).clamp(0.0, 1.0);
+        for stop in self.stops[..self.stop_count].iter_mut() {
+            let RGBA(r, g, b, a) = stop.color;
+            stop.color = RGBA(
+                offset(r, from.0, to.0),
+                offset(g, from.1, to.1),
+                offset(b, from.2, to.2),
+                offset(a, from.3, to.3),
+            );
+        }
+        self
+    }
+
+    /// 获取色标
+    pub fn stops(&self) -> &[GradientStop] {
+        &self.stops[..self.stop_count]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: RGBA = RGBA(1.0, 0.0, 0.0, 1.0);
+    const BLUE: RGBA = RGBA(0.0, 0.0, 1.0, 1.0);
+
+    #[test]
+    fn stop_count_is_checked() {
+        assert_eq!(Gradient::linear(0.0, &[]), Err(GradientError::NoStops));
+        let stops = [(0.0, RED); MAX_GRADIENT_STOPS + 1];
+        assert_eq!(
+            Gradient::linear(0.0, &stops),
+            Err(GradientError::TooManyStops(MAX_GRADIENT_STOPS + 1))
+        );
+        let gradient = Gradient::linear(0.0, &[(1.0, BLUE), (0.0, RED)]).unwrap();
+        assert_eq!(gradient.stops()[0].color, RED);
+        assert_eq!(gradient.stops().len(), 2);
+    }
+
+    #[test]
+    fn shift_moves_every_stop() {
+        let gradient = Gradient::linear(0.0, &[(0.0, RED), (1.0, BLUE)]).unwrap();
+        let shifted = gradient.shift(RED, RGBA(0.5, 0.5, 0.0, 1.0));
+        assert_eq!(shifted.stops()[0].color, RGBA(0.5, 0.5, 0.0, 1.0));
+        assert_eq!(shifted.stops()[1].color, RGBA(0.0, 0.5, 1.0, 1.0));
+    }
+}
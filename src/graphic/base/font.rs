@@ -68,18 +68,18 @@ impl Character {
         }
     }
 
+    /// 字形排版宽度（像素），即字形图像的宽度
+    /// 位图超出原点左侧时向右加宽，且不小于位图宽度
+    pub fn layout_width(&self) -> u32 {
+        let overhang = (-self.bearing_x).max(0) as u32;
+        (self.advance + overhang).max(self.width)
+    }
+
     /// 将字形转为单通道二维图像(只有alpha值)
     pub fn to_raw(&self) -> ImageRaw {
-        let mut advance = self.advance;
-        let mut bearing_x = self.bearing_x;
+        let advance = self.layout_width();
+        let bearing_x = self.bearing_x.max(0);
         let bearing_y = self.bearing_y;
-        if bearing_x < 0 {
-            advance = (advance as i32 - bearing_x) as u32;
-            bearing_x = 0;
-        }
-        if advance < self.width {
-            advance = self.width;
-        }
         let size = self.scale * advance;
         let mut buffer = vec![0; size as usize];
         for column in 0..self.height {
@@ -114,7 +114,7 @@ impl Character {
             let raw_data = self.to_raw();
             self.texture = Some(g_texture.create_bind_group(device, queue, raw_data));
         }
-        return self.texture.as_ref().unwrap();
+        self.texture.as_ref().unwrap()
     }
 }
 
@@ -136,7 +136,10 @@ fn blank_character(scale: u32) -> Character {
 /// 字形容器，保存生成的字形数据
 #[derive(Debug)]
 pub struct GCharMap {
+    /// 字体大小（逻辑像素）
     pub scale: f32,
+    /// 窗口缩放比例，字形按字体大小乘以此比例光栅化
+    pub scale_factor: f32,
     pub scaled_font: PxScaleFont<FontVec>,
     pub map: HashMap<char, Character>,
 }
@@ -162,10 +165,21 @@ impl GCharMap {
         }
         GCharMap {
             scale: font_size,
+            scale_factor: 1.0,
             scaled_font,
             map: characters,
         }
     }
+    /// 设置窗口缩放比例，比例变化时清空已生成的字形，按新尺寸重新光栅化
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        if scale_factor <= 0.0 || scale_factor == self.scale_factor {
+            return;
+        }
+        self.scale_factor = scale_factor;
+        self.scaled_font.scale = PxScale::from(self.scale * scale_factor);
+        self.map.clear();
+    }
+
     /// 获取指定字符字形
     pub fn character(&mut self, c: char) -> &Character {
         let ch = self.map.get(&c);
@@ -178,7 +192,19 @@ impl GCharMap {
                 self.map.insert(c, new_ch);
             }
         }
-        return self.map.get(&c).unwrap();
+        self.map.get(&c).unwrap()
+    }
+
+    /// 计算字符排版后的宽度（逻辑像素），`PaintBrush::draw_text`按此宽度依次排列字符
+    pub fn char_width(&mut self, c: char) -> f32 {
+        let scale = 10. / (self.scale / 2.5);
+        let scale_factor = self.scale_factor;
+        self.character(c).layout_width() as f32 / scale_factor * scale
+    }
+
+    /// 计算文本绘制后的宽度（逻辑像素），与`PaintBrush::draw_text`的排版一致
+    pub fn text_width(&mut self, text: &str) -> f32 {
+        text.chars().map(|c| self.char_width(c)).sum()
     }
 
     /// 给容器中的每个字符生成相应纹理缓冲
@@ -201,7 +227,7 @@ impl GCharMap {
             new_ch.set_texture(g_texture, device, queue);
             self.map.insert(c, new_ch);
         }
-        return self.map.get(&c).unwrap();
+        self.map.get(&c).unwrap()
     }
 
     /// 把字符串文本转换成单通道图像数据
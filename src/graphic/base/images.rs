@@ -1,9 +1,12 @@
 use std::path::Path;
+use std::rc::Rc;
 
 use image::GenericImageView;
 
+use crate::graphic::base::SvgIcon;
+
 /// 图像数据结构体
-#[derive(Debug, Hash, PartialEq, Eq)]
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct ImageRaw {
     /// 图像宽度
     pub width: u32,
@@ -15,13 +18,162 @@ pub struct ImageRaw {
 
 impl ImageRaw {
     pub fn new(image_path: &str) -> Self {
-        let image_file = image::open(Path::new(image_path)).expect("cannot open image file");
-        let raw_data = image_file.as_bytes();
+        Self::open(image_path).expect("cannot open image file")
+    }
+
+    /// 读取并解码图像文件，文件不存在或无法解码时返回错误
+    pub fn open(image_path: &str) -> image::ImageResult<Self> {
+        let image_file = image::open(Path::new(image_path))?;
         let (width, height) = image_file.dimensions();
+        Ok(ImageRaw {
+            width,
+            height,
+            data: image_file.to_rgba8().into_raw(),
+        })
+    }
+
+    /// 图像无法加载时显示的占位图像，即一个透明像素
+    pub fn placeholder() -> Self {
+        ImageRaw {
+            width: 1,
+            height: 1,
+            data: vec![0; 4],
+        }
+    }
+
+    /// 截取图像中的矩形区域，超出图像的部分被裁掉
+    pub fn crop(&self, x: u32, y: u32, width: u32, height: u32) -> ImageRaw {
+        let x = x.min(self.width);
+        let y = y.min(self.height);
+        let width = width.min(self.width - x);
+        let height = height.min(self.height - y);
+        let mut data = Vec::with_capacity((width * height * 4) as usize);
+        for row in y..y + height {
+            let start = ((row * self.width + x) * 4) as usize;
+            data.extend_from_slice(&self.data[start..start + (width * 4) as usize]);
+        }
         ImageRaw {
             width,
             height,
-            data: raw_data.to_vec(),
+            data,
+        }
+    }
+
+    /// 将RGBA图像数据保存为png文件，不论文件扩展名如何均按png格式编码
+    pub fn save_png(&self, path: &str) -> image::ImageResult<()> {
+        image::save_buffer_with_format(
+            Path::new(path),
+            &self.data,
+            self.width,
+            self.height,
+            image::ColorType::Rgba8,
+            image::ImageFormat::Png,
+        )
+    }
+}
+
+/// 图像来源枚举
+/// 渲染时按来源缓存纹理：位图文件按路径只解码一次，矢量图标按图标及像素尺寸栅格化，
+/// 克隆的来源共享同一份纹理
+#[derive(Debug, Clone)]
+pub enum ImageSource {
+    /// 位图文件路径
+    File(String),
+    /// 矢量图标，按目标尺寸栅格化
+    Svg(SvgIcon),
+    /// 已解码的图像数据
+    Raw(Rc<ImageRaw>),
+}
+
+/// 图像缓存键，标识图像来源及栅格化尺寸
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ImageKey {
+    File(String),
+    Svg {
+        icon: usize,
+        width: u32,
+        height: u32,
+    },
+    Raw(usize),
+}
+
+impl ImageSource {
+    /// 获取指定显示尺寸（物理像素）的图像数据，位图文件无法读取时返回错误
+    pub fn to_raw(&self, width: u32, height: u32) -> image::ImageResult<ImageRaw> {
+        match self {
+            ImageSource::File(path) => ImageRaw::open(path.as_str()),
+            ImageSource::Svg(icon) => Ok(icon.rasterize(width, height)),
+            ImageSource::Raw(raw) => Ok(raw.as_ref().clone()),
+        }
+    }
+
+    /// 获取指定显示尺寸下的缓存键，只有矢量图标与尺寸有关
+    /// 矢量图标及图像数据按共享指针区分，缓存需持有来源以保证指针不被复用
+    pub fn key(&self, width: u32, height: u32) -> ImageKey {
+        match self {
+            ImageSource::File(path) => ImageKey::File(path.clone()),
+            ImageSource::Svg(icon) => ImageKey::Svg {
+                icon: icon.id(),
+                width: width.max(1),
+                height: height.max(1),
+            },
+            ImageSource::Raw(raw) => ImageKey::Raw(Rc::as_ptr(raw) as usize),
         }
     }
 }
+
+impl From<String> for ImageSource {
+    fn from(path: String) -> Self {
+        ImageSource::File(path)
+    }
+}
+
+impl From<&str> for ImageSource {
+    fn from(path: &str) -> Self {
+        ImageSource::File(path.to_string())
+    }
+}
+
+impl From<ImageRaw> for ImageSource {
+    fn from(raw: ImageRaw) -> Self {
+        ImageSource::Raw(Rc::new(raw))
+    }
+}
+
+impl From<SvgIcon> for ImageSource {
+    fn from(icon: SvgIcon) -> Self {
+        ImageSource::Svg(icon)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SVG: &[u8] = br#"<svg xmlns="http://www.w3.org/2000/svg" width="4" height="4"><rect width="4" height="4"/></svg>"#;
+
+    #[test]
+    fn missing_file_is_an_error() {
+        let source = ImageSource::from("/nonexistent/image.png");
+        assert!(source.to_raw(16, 16).is_err());
+    }
+
+    #[test]
+    fn keys_follow_source_identity() {
+        let file = ImageSource::from("icon.png");
+        assert_eq!(file.key(16, 16), file.clone().key(32, 32));
+
+        let icon = ImageSource::from(SvgIcon::from_data(SVG));
+        assert_eq!(icon.key(16, 16), icon.clone().key(16, 16));
+        assert_ne!(icon.key(16, 16), icon.key(32, 32));
+        let other = ImageSource::from(SvgIcon::from_data(SVG));
+        assert_ne!(icon.key(16, 16), other.key(16, 16));
+
+        let raw = ImageSource::from(ImageRaw::placeholder());
+        assert_eq!(raw.key(1, 1), raw.clone().key(8, 8));
+        assert_ne!(
+            raw.key(1, 1),
+            ImageSource::from(ImageRaw::placeholder()).key(1, 1)
+        );
+    }
+}
@@ -2,6 +2,8 @@ pub use color::*;
 pub use font::*;
 pub use images::*;
 pub use shape::*;
+pub use svg::*;
+pub use transform::*;
 
 /// 色彩模块
 mod color;
@@ -11,3 +13,7 @@ mod font;
 mod images;
 /// 图形模块
 mod shape;
+/// 矢量图模块
+mod svg;
+/// 仿射变换模块
+mod transform;
@@ -3,7 +3,7 @@ use crate::graphic::style::Style;
 
 /// 点结构体
 #[repr(C)]
-#[derive(Copy, Default, Clone, Debug)]
+#[derive(Copy, Default, Clone, Debug, PartialEq, PartialOrd)]
 pub struct Point<T> {
     pub x: T,
     pub y: T,
@@ -46,18 +46,73 @@ impl<T> Point<T> {
     }
 }
 
+/// 填充规则枚举
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum FillRule {
+    /// 奇偶规则，被奇数条边包围的区域被填充
+    EvenOdd,
+    /// 非零规则，环绕数不为零的区域被填充
+    #[default]
+    NonZero,
+}
+
+/// 图形数据错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShapeError {
+    /// 多边形顶点数不足3个
+    TooFewPoints(usize),
+    /// 顶点坐标为NaN或无穷大
+    InvalidPoint(Point<f32>),
+    /// 图形细分失败
+    Tessellation(String),
+}
+
 /// 多边形结构体
 ///
-/// 根据给定顶点坐标绘制任意多边形
-/// 顶点顺序为多边形的逆时针排列
-#[derive(Debug)]
+/// 根据给定顶点坐标绘制任意多边形，支持凹多边形、自相交多边形及孔洞
+/// 顶点坐标范围为-1.0~1.0
+#[derive(Debug, Clone)]
 pub struct Polygon {
+    /// 外轮廓顶点
     pub points: Vec<Point<f32>>,
+    /// 孔洞轮廓顶点
+    pub holes: Vec<Vec<Point<f32>>>,
+    /// 填充规则
+    pub fill_rule: FillRule,
 }
 
 impl Polygon {
     pub fn new(points: Vec<Point<f32>>) -> Polygon {
-        Polygon { points }
+        Polygon {
+            points,
+            holes: Vec::new(),
+            fill_rule: FillRule::default(),
+        }
+    }
+    /// 添加孔洞，孔洞在非零规则下需与外轮廓方向相反
+    pub fn hole(mut self, points: Vec<Point<f32>>) -> Self {
+        self.holes.push(points);
+        self
+    }
+    /// 设置填充规则
+    pub fn fill_rule(mut self, fill_rule: FillRule) -> Self {
+        self.fill_rule = fill_rule;
+        self
+    }
+    /// 检查多边形各轮廓是否可被细分
+    pub fn validate(&self) -> Result<(), ShapeError> {
+        for contour in std::iter::once(&self.points).chain(self.holes.iter()) {
+            if contour.len() < 3 {
+                return Err(ShapeError::TooFewPoints(contour.len()));
+            }
+            if let Some(point) = contour
+                .iter()
+                .find(|p| !p.x.is_finite() || !p.y.is_finite())
+            {
+                return Err(ShapeError::InvalidPoint(*point));
+            }
+        }
+        Ok(())
     }
 }
 
@@ -70,13 +125,18 @@ impl Rectangle {
         }
     }
 
-    /// 将矩形映射到给定宽高的区域中，坐标范围变为-1.0~1.0
-    pub fn get_coord(&self, w_width: u32, w_height: u32) -> (f32, f32, f32, f32) {
+    /// 将逻辑坐标下的矩形映射到给定物理像素宽高的区域中，坐标范围变为-1.0~1.0
+    pub fn get_coord(
+        &self,
+        w_width: u32,
+        w_height: u32,
+        scale_factor: f32,
+    ) -> (f32, f32, f32, f32) {
         (
-            2.0 * self.position.x as f32 / w_width as f32 - 1.0,
-            1.0 - 2.0 * self.position.y as f32 / w_height as f32,
-            2.0 * self.width as f32 / w_width as f32,
-            2.0 * self.height as f32 / w_height as f32,
+            2.0 * self.position.x * scale_factor / w_width as f32 - 1.0,
+            1.0 - 2.0 * self.position.y * scale_factor / w_height as f32,
+            2.0 * self.width as f32 * scale_factor / w_width as f32,
+            2.0 * self.height as f32 * scale_factor / w_height as f32,
         )
     }
 
@@ -106,8 +166,224 @@ impl RegularPolygon {
     }
 }
 
+/// 线段端点样式
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum LineCap {
+    /// 平头，端点处不延伸
+    Butt,
+    /// 方头，端点处延伸半个线宽
+    Square,
+    /// 圆头
+    Round,
+}
+
+/// 线段连接样式
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum LineJoin {
+    /// 尖角
+    Miter,
+    /// 圆角
+    Round,
+    /// 斜角
+    Bevel,
+}
+
+/// 描边样式结构体
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stroke {
+    /// 线宽
+    pub width: f32,
+    /// 端点样式
+    pub cap: LineCap,
+    /// 连接样式
+    pub join: LineJoin,
+    /// 虚线模式，依次为实线长度和间隔长度，为空时绘制实线
+    pub dash: Vec<f32>,
+    /// 虚线起始偏移
+    pub dash_offset: f32,
+}
+
+/// 线段结构体
+#[derive(Debug, Clone)]
+pub struct Line {
+    /// 起点坐标
+    pub start: Point<f32>,
+    /// 终点坐标
+    pub end: Point<f32>,
+    /// 描边样式
+    pub stroke: Stroke,
+}
+
+/// 折线结构体
+#[derive(Debug, Clone)]
+pub struct Polyline {
+    /// 顶点坐标
+    pub points: Vec<Point<f32>>,
+    /// 是否首尾相连
+    pub closed: bool,
+    /// 描边样式
+    pub stroke: Stroke,
+}
+
+/// 路径片段枚举
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PathSegment {
+    /// 移动到指定点，开始新的子路径
+    MoveTo(Point<f32>),
+    /// 直线
+    LineTo(Point<f32>),
+    /// 二次贝塞尔曲线
+    QuadTo { ctrl: Point<f32>, to: Point<f32> },
+    /// 三次贝塞尔曲线
+    CubicTo {
+        ctrl1: Point<f32>,
+        ctrl2: Point<f32>,
+        to: Point<f32>,
+    },
+    /// 以当前点为起点绕圆心的椭圆弧，扫过角度为弧度，正值为顺时针
+    ArcTo {
+        center: Point<f32>,
+        radius: Point<f32>,
+        sweep_angle: f32,
+    },
+    /// 闭合当前子路径
+    Close,
+}
+
+/// 路径结构体
+///
+/// 由直线、贝塞尔曲线和圆弧组成，坐标为窗口像素坐标
+/// 设置描边样式时绘制轮廓，否则填充路径内部
+#[derive(Debug, Clone, Default)]
+pub struct Path {
+    /// 路径片段
+    pub segments: Vec<PathSegment>,
+    /// 描边样式
+    pub stroke: Option<Stroke>,
+    /// 填充规则
+    pub fill_rule: FillRule,
+}
+
+impl Stroke {
+    pub fn new(width: f32) -> Stroke {
+        Stroke {
+            width,
+            cap: LineCap::Butt,
+            join: LineJoin::Miter,
+            dash: Vec::new(),
+            dash_offset: 0.0,
+        }
+    }
+    /// 设置端点样式
+    pub fn cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+    /// 设置连接样式
+    pub fn join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+    /// 设置虚线模式，忽略非正数的长度
+    pub fn dash(mut self, pattern: &[f32], offset: f32) -> Self {
+        self.dash = pattern.iter().copied().filter(|len| *len > 0.0).collect();
+        self.dash_offset = offset;
+        self
+    }
+}
+
+impl Default for Stroke {
+    fn default() -> Self {
+        Stroke::new(1.0)
+    }
+}
+
+impl Line {
+    pub fn new(start: Point<f32>, end: Point<f32>, stroke: Stroke) -> Line {
+        Line { start, end, stroke }
+    }
+}
+
+impl Polyline {
+    pub fn new(points: Vec<Point<f32>>, stroke: Stroke) -> Polyline {
+        Polyline {
+            points,
+            closed: false,
+            stroke,
+        }
+    }
+    /// 首尾相连
+    pub fn close(mut self) -> Self {
+        self.closed = true;
+        self
+    }
+}
+
+impl Path {
+    pub fn new() -> Path {
+        Path::default()
+    }
+    pub fn move_to(mut self, to: Point<f32>) -> Self {
+        self.segments.push(PathSegment::MoveTo(to));
+        self
+    }
+    pub fn line_to(mut self, to: Point<f32>) -> Self {
+        self.segments.push(PathSegment::LineTo(to));
+        self
+    }
+    pub fn quad_to(mut self, ctrl: Point<f32>, to: Point<f32>) -> Self {
+        self.segments.push(PathSegment::QuadTo { ctrl, to });
+        self
+    }
+    pub fn cubic_to(mut self, ctrl1: Point<f32>, ctrl2: Point<f32>, to: Point<f32>) -> Self {
+        self.segments
+            .push(PathSegment::CubicTo { ctrl1, ctrl2, to });
+        self
+    }
+    pub fn arc_to(mut self, center: Point<f32>, radius: Point<f32>, sweep_angle: f32) -> Self {
+        self.segments.push(PathSegment::ArcTo {
+            center,
+            radius,
+            sweep_angle,
+        });
+        self
+    }
+    pub fn close(mut self) -> Self {
+        self.segments.push(PathSegment::Close);
+        self
+    }
+    /// 设置描边样式，路径将绘制为轮廓
+    pub fn stroke(mut self, stroke: Stroke) -> Self {
+        self.stroke = Some(stroke);
+        self
+    }
+    /// 设置填充规则
+    pub fn fill_rule(mut self, fill_rule: FillRule) -> Self {
+        self.fill_rule = fill_rule;
+        self
+    }
+}
+
+impl std::fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShapeError::TooFewPoints(count) => {
+                write!(f, "polygon needs at least 3 points, got {}", count)
+            }
+            ShapeError::InvalidPoint(point) => write!(f, "invalid point: {:?}", point),
+            ShapeError::Tessellation(error) => write!(f, "tessellation failed: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for ShapeError {}
+
 /// 图形缓冲转换接口
 pub trait ShapeGraph {
     /// 转换为顶点缓冲数据
     fn to_buffer(&self, gpu_context: &GPUContext, style: Style) -> VBuffer;
+    /// 矩形图形返回自身，用于绘制阴影及背景模糊，其他图形不绘制这些效果
+    fn get_rect(&self) -> Option<Rectangle> {
+        None
+    }
 }
@@ -0,0 +1,103 @@
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+
+use resvg::{tiny_skia, usvg};
+
+use crate::graphic::base::ImageRaw;
+
+/// 每个图标最多缓存的栅格化尺寸数
+const SVG_CACHE_LIMIT: usize = 4;
+
+/// 栅格化缓存，每项为((宽, 高), 图像)
+type RasterCache = Vec<((u32, u32), ImageRaw)>;
+
+/// 矢量图标结构体
+///
+/// 保存解析后的svg文档，按目标尺寸栅格化为RGBA图像，
+/// 并缓存最近使用的几种尺寸的栅格化结果，克隆后的图标共享同一份缓存
+#[derive(Clone)]
+pub struct SvgIcon {
+    /// svg文档树
+    tree: Rc<usvg::Tree>,
+    /// 栅格化缓存，按最近使用顺序排列，最后一项为最近使用
+    cache: Rc<RefCell<RasterCache>>,
+}
+
+impl SvgIcon {
+    /// 从svg文件创建图标
+    pub fn new(svg_path: &str) -> Self {
+        let data = std::fs::read(Path::new(svg_path)).expect("cannot open svg file");
+        Self::from_data(data.as_slice())
+    }
+
+    /// 从svg文本数据创建图标
+    pub fn from_data(data: &[u8]) -> Self {
+        let tree =
+            usvg::Tree::from_data(data, &usvg::Options::default()).expect("cannot parse svg data");
+        SvgIcon {
+            tree: Rc::new(tree),
+            cache: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    /// 获取svg文档的原始尺寸
+    pub fn size(&self) -> (f32, f32) {
+        let size = self.tree.size();
+        (size.width(), size.height())
+    }
+
+    /// 图标标识，克隆的图标标识相同
+    pub(crate) fn id(&self) -> usize {
+        Rc::as_ptr(&self.tree) as usize
+    }
+
+    /// 将图标栅格化为指定宽高的RGBA图像，缓存中已有该尺寸时不再栅格化
+    /// 缓存超过SVG_CACHE_LIMIT种尺寸时丢弃最久未使用的尺寸
+    pub fn rasterize(&self, width: u32, height: u32) -> ImageRaw {
+        let width = width.max(1);
+        let height = height.max(1);
+        let mut cache = self.cache.borrow_mut();
+        if let Some(index) = cache.iter().position(|(size, _)| *size == (width, height)) {
+            let entry = cache.remove(index);
+            let raw = entry.1.clone();
+            cache.push(entry);
+            return raw;
+        }
+        log::info!("rasterize svg icon: {}x{}", width, height);
+        let raw = self.render(width, height);
+        if cache.len() >= SVG_CACHE_LIMIT {
+            cache.remove(0);
+        }
+        cache.push(((width, height), raw.clone()));
+        raw
+    }
+
+    /// 渲染svg，tiny_skia输出预乘alpha的像素，需还原为直通alpha
+    fn render(&self, width: u32, height: u32) -> ImageRaw {
+        let mut pixmap = tiny_skia::Pixmap::new(width, height).expect("invalid svg target size");
+        let (svg_width, svg_height) = self.size();
+        let transform =
+            tiny_skia::Transform::from_scale(width as f32 / svg_width, height as f32 / svg_height);
+        resvg::render(&self.tree, transform, &mut pixmap.as_mut());
+        let mut data = Vec::with_capacity((width * height * 4) as usize);
+        for pixel in pixmap.pixels() {
+            let color = pixel.demultiply();
+            data.extend_from_slice(&[color.red(), color.green(), color.blue(), color.alpha()]);
+        }
+        ImageRaw {
+            width,
+            height,
+            data,
+        }
+    }
+}
+
+impl std::fmt::Debug for SvgIcon {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SvgIcon")
+            .field("size", &self.size())
+            .field("cached", &self.cache.borrow().len())
+            .finish()
+    }
+}
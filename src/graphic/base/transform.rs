@@ -0,0 +1,127 @@
+use crate::graphic::base::Point;
+
+/// 二维仿射变换结构体
+///
+/// 点(x, y)变换为(a*x + c*y + e, b*x + d*y + f)，
+/// 与canvas的用法相同，链式调用的变换按调用顺序作用于局部坐标系，
+/// 即`Transform::identity().translate(x, y).rotate(r)`先旋转再平移
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub e: f32,
+    pub f: f32,
+}
+
+impl Transform {
+    /// 单位变换
+    pub fn identity() -> Transform {
+        Transform {
+            a: 1.0,
+            b: 0.0,
+            c: 0.0,
+            d: 1.0,
+            e: 0.0,
+            f: 0.0,
+        }
+    }
+
+    /// 平移变换
+    pub fn translation(x: f32, y: f32) -> Transform {
+        Transform {
+            e: x,
+            f: y,
+            ..Transform::identity()
+        }
+    }
+
+    /// 缩放变换
+    pub fn scaling(x: f32, y: f32) -> Transform {
+        Transform {
+            a: x,
+            d: y,
+            ..Transform::identity()
+        }
+    }
+
+    /// 旋转变换，参数为弧度，y轴向下时顺时针为正
+    pub fn rotation(angle: f32) -> Transform {
+        let (sin, cos) = angle.sin_cos();
+        Transform {
+            a: cos,
+            b: sin,
+            c: -sin,
+            d: cos,
+            ..Transform::identity()
+        }
+    }
+
+    /// 在当前变换的局部坐标系中平移
+    pub fn translate(self, x: f32, y: f32) -> Transform {
+        self.then(&Transform::translation(x, y))
+    }
+
+    /// 在当前变换的局部坐标系中缩放
+    pub fn scale(self, x: f32, y: f32) -> Transform {
+        self.then(&Transform::scaling(x, y))
+    }
+
+    /// 在当前变换的局部坐标系中旋转
+    pub fn rotate(self, angle: f32) -> Transform {
+        self.then(&Transform::rotation(angle))
+    }
+
+    /// 组合变换，local先作用于点，再作用当前变换
+    pub fn then(&self, local: &Transform) -> Transform {
+        Transform {
+            a: self.a * local.a + self.c * local.b,
+            b: self.b * local.a + self.d * local.b,
+            c: self.a * local.c + self.c * local.d,
+            d: self.b * local.c + self.d * local.d,
+            e: self.a * local.e + self.c * local.f + self.e,
+            f: self.b * local.e + self.d * local.f + self.f,
+        }
+    }
+
+    /// 求逆变换，不可逆时返回None
+    pub fn inverse(&self) -> Option<Transform> {
+        let det = self.a * self.d - self.b * self.c;
+        if det.abs() <= f32::EPSILON {
+            return None;
+        }
+        Some(Transform {
+            a: self.d / det,
+            b: -self.b / det,
+            c: -self.c / det,
+            d: self.a / det,
+            e: (self.c * self.f - self.d * self.e) / det,
+            f: (self.b * self.e - self.a * self.f) / det,
+        })
+    }
+
+    /// 变换点坐标
+    pub fn apply(&self, point: Point<f32>) -> Point<f32> {
+        Point::new(
+            self.a * point.x + self.c * point.y + self.e,
+            self.b * point.x + self.d * point.y + self.f,
+        )
+    }
+
+    /// 转换为着色器使用的按列排列的4x4矩阵
+    pub fn to_mat4(&self) -> [[f32; 4]; 4] {
+        [
+            [self.a, self.b, 0.0, 0.0],
+            [self.c, self.d, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [self.e, self.f, 0.0, 1.0],
+        ]
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform::identity()
+    }
+}
@@ -7,6 +7,7 @@ pub trait PaintBrush {
     fn clear_frame(&mut self, color: RGBA);
 
     /// 绘制图形
+    #[allow(clippy::borrowed_box)]
     fn draw_shape(&mut self, shape: &Box<dyn ShapeGraph>, shape_style: Style);
 
     /// 绘制文本
@@ -18,6 +19,25 @@ pub trait PaintBrush {
         text_color: RGBA,
     );
 
-    /// 绘制图像
-    fn draw_image(&mut self, image_rect: &Rectangle, image: ImageRaw);
+    /// 绘制图像，矢量图标按区域的物理像素尺寸栅格化
+    fn draw_image(&mut self, image_rect: &Rectangle, image: &ImageSource);
+
+    /// 压入变换，之后的绘制均在此变换（与父级变换组合后）的坐标系中进行
+    fn push_transform(&mut self, transform: Transform);
+
+    /// 弹出最近压入的变换
+    fn pop_transform(&mut self);
+
+    /// 压入图层，之后的绘制先绘制到离屏图层中，弹出时按不透明度合成
+    fn push_layer(&mut self, opacity: f32);
+
+    /// 弹出最近压入的图层，并将其合成到上一级图层
+    fn pop_layer(&mut self);
+
+    /// 压入裁剪区域，之后的绘制只在此区域与父级裁剪区域的交集内可见
+    /// 区域坐标受当前变换影响，旋转后取其外接矩形
+    fn push_clip(&mut self, clip_rect: Rectangle);
+
+    /// 弹出最近压入的裁剪区域
+    fn pop_clip(&mut self);
 }
@@ -1,23 +1,70 @@
+use crate::graphic::animation::Lerp;
 use crate::graphic::base::*;
 
+/// 默认边框宽度
+pub const DEFAULT_BORDER_WIDTH: f32 = 1.0;
+/// 默认圆角半径
+pub const DEFAULT_ROUND_RADIUS: f32 = 8.0;
+
+/// 边框线型枚举
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum BorderLine {
+    /// 实线
+    Solid,
+    /// 虚线，参数为线段长度和间隔长度
+    Dashed(f32, f32),
+}
+
 /// 边框枚举
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
 pub enum Bordering {
-    /// 边框颜色
-    Border(RGBA),
+    /// 边框颜色，宽度和线型
+    Border {
+        color: RGBA,
+        width: f32,
+        line: BorderLine,
+    },
     /// 无边框
+    #[default]
     NoBorder,
 }
 
 /// 圆角枚举
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
 pub enum Rounding {
-    /// 圆角宽度和颜色
-    Round,
+    /// 各角圆角半径，依次为左上、右上、右下、左下
+    Round([f32; 4]),
+    /// 胶囊形，圆角半径为短边的一半
+    Pill,
     /// 无圆角
+    #[default]
     NoRound,
 }
 
+/// 阴影结构体
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BoxShadow {
+    /// 阴影相对图形的偏移
+    pub offset: Point<f32>,
+    /// 模糊半径
+    pub blur: f32,
+    /// 扩展距离，为正时阴影大于图形
+    pub spread: f32,
+    /// 阴影颜色
+    pub color: RGBA,
+}
+
+impl BoxShadow {
+    pub fn new(offset: Point<f32>, blur: f32, spread: f32, color: RGBA) -> BoxShadow {
+        BoxShadow {
+            offset,
+            blur,
+            spread,
+            color,
+        }
+    }
+}
+
 /// 字体样式枚举
 #[derive(Copy, Clone, Debug)]
 pub enum FontStyle {
@@ -38,8 +85,31 @@ pub struct ShapeStyle {
     back_color: RGBA,
     /// 悬浮色
     hover_color: RGBA,
+    /// 按下时的颜色
+    pressed_color: RGBA,
+    /// 禁用时的颜色
+    disabled_color: RGBA,
     // 默认背景显示颜色
     display_color: RGBA,
+    /// 背景渐变色
+    gradient: Option<Gradient>,
+    /// 阴影
+    shadow: Option<BoxShadow>,
+    /// 背景模糊半径，为0时不模糊
+    backdrop_blur: f32,
+}
+
+/// 组件交互状态
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Interaction {
+    /// 默认状态
+    Normal,
+    /// 鼠标悬停
+    Hovered,
+    /// 按下
+    Pressed,
+    /// 禁用
+    Disabled,
 }
 
 /// 样式结构体
@@ -52,32 +122,90 @@ pub struct Style {
     shape_style: ShapeStyle,
 }
 
-impl Style {
-    pub fn default() -> Style {
+impl Default for Style {
+    fn default() -> Style {
         Style {
             font_style: FontStyle::NoFont,
             shape_style: ShapeStyle {
-                border: Bordering::Border(BLACK),
+                border: Bordering::Border {
+                    color: BLACK,
+                    width: DEFAULT_BORDER_WIDTH,
+                    line: BorderLine::Solid,
+                },
                 round: Rounding::NoRound,
                 back_color: LIGHT_WHITE,
-                hover_color: LIGHT_BLUE,
+                hover_color: RGBA(0.0, 0.75, 1.0, 0.3),
+                pressed_color: LIGHT_BLUE,
+                disabled_color: RGBA(0.6, 0.6, 0.6, 1.0),
                 display_color: LIGHT_WHITE,
+                gradient: None,
+                shadow: None,
+                backdrop_blur: 0.0,
             },
         }
     }
-    /// 组件样式设为有边框，并设置边框颜色
+}
+
+impl Style {
+    /// 组件样式设为有边框，并设置边框颜色，保留已设置的宽度和线型
     pub fn border(&mut self, color: RGBA) -> Self {
-        self.shape_style.border = Bordering::Border(color);
+        let (width, line) = self.border_width_and_line();
+        self.shape_style.border = Bordering::Border { color, width, line };
         *self
     }
+    /// 设置组件边框宽度
+    pub fn border_width(&mut self, width: f32) -> Self {
+        let (_, line) = self.border_width_and_line();
+        let color = self.border_color();
+        self.shape_style.border = Bordering::Border { color, width, line };
+        *self
+    }
+    /// 组件边框设为虚线
+    /// 参数：线段长度，间隔长度
+    pub fn dashed_border(&mut self, dash: f32, gap: f32) -> Self {
+        let (width, _) = self.border_width_and_line();
+        let color = self.border_color();
+        self.shape_style.border = Bordering::Border {
+            color,
+            width,
+            line: BorderLine::Dashed(dash, gap),
+        };
+        *self
+    }
+    fn border_width_and_line(&self) -> (f32, BorderLine) {
+        match self.shape_style.border {
+            Bordering::Border { width, line, .. } => (width, line),
+            Bordering::NoBorder => (DEFAULT_BORDER_WIDTH, BorderLine::Solid),
+        }
+    }
+    fn border_color(&self) -> RGBA {
+        match self.shape_style.border {
+            Bordering::Border { color, .. } => color,
+            Bordering::NoBorder => BLACK,
+        }
+    }
     /// 组件样式设为无边框
     pub fn no_border(&mut self) -> Self {
         self.shape_style.border = Bordering::NoBorder;
         *self
     }
-    /// 组件样式设为圆角
+    /// 组件样式设为默认半径的圆角
     pub fn round(&mut self) -> Self {
-        self.shape_style.round = Rounding::Round;
+        self.round_radius(DEFAULT_ROUND_RADIUS)
+    }
+    /// 组件样式设为指定半径的圆角
+    pub fn round_radius(&mut self, radius: f32) -> Self {
+        self.shape_style.round = Rounding::Round([radius; 4]);
+        *self
+    }
+    /// 分别指定各角的圆角半径，依次为左上、右上、右下、左下
+    pub fn corner_radii(&mut self, radii: [f32; 4]) -> Self {
+        self.shape_style.round = Rounding::Round(radii);
+        *self
+    }
+    /// 组件样式设为胶囊形
+    pub fn pill(&mut self) -> Self {
+        self.shape_style.round = Rounding::Pill;
         *self
     }
     /// 组件样式设为无圆角
@@ -94,6 +222,34 @@ impl Style {
     pub fn back_color(&mut self, color: RGBA) -> Self {
         self.shape_style.back_color = color;
         self.shape_style.display_color = color;
+        self.shape_style.gradient = None;
+        *self
+    }
+    /// 指定组件背景渐变色，背景色取渐变的首个色标颜色
+    pub fn back_gradient(&mut self, gradient: Gradient) -> Self {
+        let color = gradient
+            .stops()
+            .first()
+            .map(|stop| stop.color)
+            .unwrap_or(self.shape_style.back_color);
+        self.shape_style.back_color = color;
+        self.shape_style.display_color = color;
+        self.shape_style.gradient = Some(gradient);
+        *self
+    }
+    /// 设置组件阴影
+    pub fn shadow(&mut self, shadow: BoxShadow) -> Self {
+        self.shape_style.shadow = Some(shadow);
+        *self
+    }
+    /// 组件样式设为无阴影
+    pub fn no_shadow(&mut self) -> Self {
+        self.shape_style.shadow = None;
+        *self
+    }
+    /// 设置组件背景模糊半径，用于半透明的浮层面板
+    pub fn backdrop_blur(&mut self, radius: f32) -> Self {
+        self.shape_style.backdrop_blur = radius.max(0.0);
         *self
     }
     /// 指定组件悬停色
@@ -101,6 +257,21 @@ impl Style {
         self.shape_style.hover_color = color;
         *self
     }
+    /// 指定组件按下时的颜色
+    pub fn pressed_color(&mut self, color: RGBA) -> Self {
+        self.shape_style.pressed_color = color;
+        *self
+    }
+    /// 指定组件禁用时的颜色
+    pub fn disabled_color(&mut self, color: RGBA) -> Self {
+        self.shape_style.disabled_color = color;
+        *self
+    }
+    /// 按交互状态设置组件前景色
+    pub fn interaction(&mut self, interaction: Interaction) -> Self {
+        self.shape_style.display_color = self.get_interaction_color(interaction);
+        *self
+    }
     /// 设置组件前景色
     pub fn display_color(&mut self, color: RGBA) -> Self {
         self.shape_style.display_color = color;
@@ -130,28 +301,120 @@ impl Style {
         self.shape_style.hover_color
     }
 
+    pub fn get_pressed_color(&self) -> RGBA {
+        self.shape_style.pressed_color
+    }
+
+    pub fn get_disabled_color(&self) -> RGBA {
+        self.shape_style.disabled_color
+    }
+
+    /// 获取交互状态对应的颜色
+    pub fn get_interaction_color(&self, interaction: Interaction) -> RGBA {
+        match interaction {
+            Interaction::Normal => self.shape_style.back_color,
+            Interaction::Hovered => self.shape_style.hover_color,
+            Interaction::Pressed => self.shape_style.pressed_color,
+            Interaction::Disabled => self.shape_style.disabled_color,
+        }
+    }
+
     pub fn get_display_color(&self) -> RGBA {
         self.shape_style.display_color
     }
+
+    pub fn get_shadow(&self) -> Option<&BoxShadow> {
+        self.shape_style.shadow.as_ref()
+    }
+
+    pub fn get_backdrop_blur(&self) -> f32 {
+        self.shape_style.backdrop_blur
+    }
+
+    pub fn get_gradient(&self) -> Option<&Gradient> {
+        self.shape_style.gradient.as_ref()
+    }
+
+    /// 获取形状的填充颜色
+    /// 有渐变时，前景色被改变（如悬停、按下及其过渡动画）后按前景色与背景色的差值为渐变着色
+    pub fn get_paint(&self) -> Paint {
+        let shape_style = &self.shape_style;
+        match shape_style.gradient {
+            Some(gradient) if shape_style.display_color == shape_style.back_color => {
+                Paint::Gradient(gradient)
+            }
+            Some(gradient) => {
+                Paint::Gradient(gradient.shift(shape_style.back_color, shape_style.display_color))
+            }
+            None => Paint::Solid(shape_style.display_color),
+        }
+    }
 }
 
-impl Default for Bordering {
-    fn default() -> Self {
-        Bordering::NoBorder
+/// 样式插值，颜色、边框宽度、圆角半径、阴影及模糊半径平滑过渡，
+/// 其他无法插值的属性（如边框线型、渐变）直接取目标值
+impl Lerp for Style {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        let (from_shape, to_shape) = (&self.shape_style, &to.shape_style);
+        let font_style = match (self.font_style, to.font_style) {
+            (FontStyle::Font(a), FontStyle::Font(b)) => FontStyle::Font(a.lerp(&b, t)),
+            (_, font_style) => font_style,
+        };
+        let border = match (from_shape.border, to_shape.border) {
+            (
+                Bordering::Border {
+                    color: from_color,
+                    width: from_width,
+                    ..
+                },
+                Bordering::Border { color, width, line },
+            ) => Bordering::Border {
+                color: from_color.lerp(&color, t),
+                width: from_width.lerp(&width, t),
+                line,
+            },
+            (_, border) => border,
+        };
+        let round = match (from_shape.round, to_shape.round) {
+            (Rounding::Round(a), Rounding::Round(b)) => {
+                Rounding::Round([0, 1, 2, 3].map(|i| a[i].lerp(&b[i], t)))
+            }
+            (_, round) => round,
+        };
+        let shadow = match (from_shape.shadow, to_shape.shadow) {
+            (Some(a), Some(b)) => Some(BoxShadow {
+                offset: a.offset.lerp(&b.offset, t),
+                blur: a.blur.lerp(&b.blur, t),
+                spread: a.spread.lerp(&b.spread, t),
+                color: a.color.lerp(&b.color, t),
+            }),
+            (_, shadow) => shadow,
+        };
+        Style {
+            font_style,
+            shape_style: ShapeStyle {
+                border,
+                round,
+                back_color: from_shape.back_color.lerp(&to_shape.back_color, t),
+                hover_color: from_shape.hover_color.lerp(&to_shape.hover_color, t),
+                pressed_color: from_shape.pressed_color.lerp(&to_shape.pressed_color, t),
+                disabled_color: from_shape.disabled_color.lerp(&to_shape.disabled_color, t),
+                display_color: from_shape.display_color.lerp(&to_shape.display_color, t),
+                gradient: to_shape.gradient,
+                shadow,
+                backdrop_blur: from_shape.backdrop_blur.lerp(&to_shape.backdrop_blur, t),
+            },
+        }
     }
 }
-//
-// impl Bordering {
-//     pub fn get_color(&self) -> RGBA {
-//         match self {
-//             Bordering::Border(color) => color.clone(),
-//             Bordering::NoBorder => RGBA([0.0, 0.0, 0.0, 0.0])
-//         }
-//     }
-// }
-
-impl Default for Rounding {
-    fn default() -> Self {
-        Rounding::NoRound
+
+impl Rounding {
+    /// 获取给定尺寸矩形的各角圆角半径，依次为左上、右上、右下、左下
+    pub fn get_radii(&self, width: f32, height: f32) -> [f32; 4] {
+        match self {
+            Rounding::Round(radii) => *radii,
+            Rounding::Pill => [width.min(height) / 2.0; 4],
+            Rounding::NoRound => [0.0; 4],
+        }
     }
 }
@@ -1,33 +1,67 @@
 use std::fmt::Debug;
 
-use crate::adapter::DisplayWindow;
-use crate::graphic::base::Point;
+use futures::executor::block_on;
+
+use crate::adapter::{DisplayWindow, GPUContext};
+use crate::event::{Command, Subscription, Timer};
+use crate::graphic::base::{GCharMap, Point, DEFAULT_FONT_SIZE};
 use crate::widget::{Frame, Panel};
 
 /// 实例 trait
 /// 用于定义具体应用
 pub trait Instance {
-    type M: 'static + Clone + PartialEq + Debug;
+    type M: 'static + Clone + PartialEq + Send + Debug;
     /// 新建实例
     fn new() -> Self;
     /// 组件布局
     fn layout(&self) -> Panel<Self::M>;
-    /// 状态更新
-    fn update(&mut self, _broadcast: &Self::M) {}
+    /// 状态更新，返回的命令中的异步任务在后台执行，结果作为消息再次交给`update`
+    fn update(&mut self, _broadcast: &Self::M) -> Command<Self::M> {
+        Command::none()
+    }
     /// 窗体设置
     fn setting() -> Setting;
+    /// 实例加入窗口帧时注册的定时器，到期时将消息交给`update`处理
+    fn timers(&self) -> Vec<Timer<Self::M>> {
+        Vec::new()
+    }
+    /// 声明长期运行的订阅，每次状态更新后重新调用
+    /// id不变的订阅保持运行，新出现的订阅被启动，不再声明的订阅被停止
+    fn subscription(&self) -> Subscription<Self::M> {
+        Subscription::none()
+    }
+    /// 注册自定义渲染管道，在创建窗口后、绘制前调用
+    fn register_pipelines(_gpu_context: &mut GPUContext) {}
     /// 运行实例
     fn run()
     where
         Self: 'static + Sized,
     {
         let setting = Self::setting();
-        let window = DisplayWindow::new(setting);
+        let mut window = DisplayWindow::new(setting);
+        Self::register_pipelines(&mut window.gpu_context);
         let mut frame = Frame::new();
-        let instance = Self::new();
-        frame.add_instance(instance);
+        // 定时器及订阅在事件循环开始时启动，消息发送给该实例
+        frame.add_instance(Self::new());
         window.start(frame)
     }
+    /// 不创建窗口、不进入事件循环，将新建实例的初始界面绘制到离屏帧缓冲并保存为png截图，
+    /// 用于生成文档配图，截图尺寸为窗体设置的尺寸，缩放比例为1
+    /// 运行中的界面截图由`update`返回`Command::capture`完成
+    fn screenshot(path: &str) -> image::ImageResult<()>
+    where
+        Self: 'static + Sized,
+    {
+        let setting = Self::setting();
+        let size = Point::new(setting.size.x as u32, setting.size.y as u32);
+        let mut gpu_context = block_on(GPUContext::headless(size, setting.sample_count));
+        Self::register_pipelines(&mut gpu_context);
+        let mut font_map = GCharMap::new(setting.font_path, DEFAULT_FONT_SIZE);
+        let mut frame = Frame::new();
+        frame.add_instance(Self::new());
+        let image = gpu_context.capture(&frame, &mut font_map);
+        image.save_png(path)
+    }
 }
 
 /// 窗口配置结构体
@@ -37,6 +71,8 @@ pub struct Setting {
     pub icon_path: Option<String>,
     pub font_path: String,
     pub size: Point<f32>,
+    /// 多重采样抗锯齿的采样数，1为关闭，显卡不支持时回退为1
+    pub sample_count: u32,
 }
 
 impl Default for Setting {
@@ -50,6 +86,7 @@ impl Default for Setting {
             )
             .into(),
             size: Point::new(40., 40.),
+            sample_count: 4,
         }
     }
 }
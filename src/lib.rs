@@ -1,3 +1,5 @@
+#![allow(non_snake_case)]
+
 /// 上层抽象和底层绑定的适配层
 pub mod adapter;
 /// 底层依赖的图形库和窗口库
@@ -1,13 +1,17 @@
 pub use button::*;
+pub use canvas::*;
 pub use component::*;
 pub use drawing_board::*;
 pub use frame::*;
 pub use label::*;
+pub use map::*;
 pub use panel::*;
 pub use text_input::*;
 
 /// 按钮
 mod button;
+/// 即时绘制画布
+mod canvas;
 /// 定义gui控件接口
 mod component;
 /// 图形画板
@@ -16,6 +20,8 @@ mod drawing_board;
 mod frame;
 /// 组件内容显示板
 mod label;
+/// 子组件消息映射
+mod map;
 /// 面板容器
 mod panel;
 /// 文本输入框
@@ -1,12 +1,18 @@
 use std::fmt::Debug;
 use std::option::Option::Some;
 
+use std::time::Duration;
+
 use crate::event::*;
+use crate::graphic::animation::Easing;
 use crate::graphic::base::*;
 use crate::graphic::render_api::PaintBrush;
 use crate::graphic::style::*;
 use crate::widget::*;
 
+/// 按钮交互状态变化时颜色过渡的时长
+const PRESS_TRANSITION: Duration = Duration::from_millis(120);
+
 /// 按钮控件结构体
 #[derive(Debug)]
 pub struct Button<M: Clone> {
@@ -14,13 +20,30 @@ pub struct Button<M: Clone> {
     pub button_label: Label,
     /// 控件状态
     pub bind_event: BindEvent<M>,
+    /// 鼠标是否悬停
+    hovered: bool,
+    /// 是否按下
+    pressed: bool,
+    /// 是否禁用
+    disabled: bool,
 }
 
-impl<'a, M: Clone + PartialEq> Button<M> {
+/// 按钮在布局重建后需要保留的状态
+#[derive(Debug, Copy, Clone)]
+struct ButtonState {
+    hovered: bool,
+    pressed: bool,
+    label: LabelState,
+}
+
+impl<M: Clone + PartialEq> Button<M> {
     pub fn new_with_style<S: Into<String>>(rect: Rectangle, style: Style, text: S) -> Self {
         Self {
             button_label: Label::new_text_label(rect, style, text.into()),
             bind_event: BindEvent::default(),
+            hovered: false,
+            pressed: false,
+            disabled: false,
         }
     }
 
@@ -31,9 +54,55 @@ impl<'a, M: Clone + PartialEq> Button<M> {
         Self {
             button_label: Label::new_text_label(rect, style, text),
             bind_event: BindEvent::default(),
+            hovered: false,
+            pressed: false,
+            disabled: false,
         }
     }
 
+    /// 设置是否禁用，禁用的按钮不响应事件
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self.pressed = false;
+        let interaction = self.interaction();
+        self.button_label.style.interaction(interaction);
+        self
+    }
+
+    /// 是否禁用
+    pub fn is_disabled(&self) -> bool {
+        self.disabled
+    }
+
+    /// 当前交互状态
+    pub fn interaction(&self) -> Interaction {
+        if self.disabled {
+            Interaction::Disabled
+        } else if self.pressed {
+            Interaction::Pressed
+        } else if self.hovered {
+            Interaction::Hovered
+        } else {
+            Interaction::Normal
+        }
+    }
+
+    /// 按交互状态更新显示颜色，颜色变化时平滑过渡
+    fn update_interaction(&mut self) {
+        let mut style = self.button_label.style;
+        style.interaction(self.interaction());
+        if style.get_display_color() != self.button_label.style.get_display_color() {
+            self.button_label
+                .animate(style, PRESS_TRANSITION, Easing::EaseOut);
+        }
+    }
+
+    /// 设置按钮图标
+    pub fn icon<I: Into<ImageSource>>(mut self, icon: I) -> Self {
+        self.button_label.image = Some(icon.into());
+        self
+    }
+
     /// 更新状态
     pub fn action(mut self, message: M) -> Self {
         self.bind_event.message = Some(message);
@@ -51,16 +120,27 @@ impl<'a, M: Clone + PartialEq> Button<M> {
         }
         false
     }
-    fn action_listener(&mut self, event_context: &dyn EventContext<M>, mouse: Mouse) -> bool {
-        if mouse == self.bind_event.mouse {
-            return component::action_animation(
-                event_context,
-                &mut self.button_label.style,
-                &self.button_label.size,
-                self.bind_event.message.clone(),
-            );
+    fn action_listener(&mut self, event_context: &mut dyn EventContext<M>, mouse: Mouse) -> bool {
+        if mouse != self.bind_event.mouse {
+            return false;
         }
-        false
+        match event_context.get_event().state {
+            State::Pressed if self.hovered => {
+                self.pressed = true;
+                if let Some(message) = self.bind_event.message.clone() {
+                    event_context.send_message(message);
+                }
+                // 按下后捕获指针，在按钮外松开时也能恢复状态
+                event_context.capture_pointer();
+            }
+            State::Released if self.pressed => {
+                self.pressed = false;
+            }
+            _ => return false,
+        }
+        // 单击已由此按钮处理，下层组件不再响应
+        event_context.consume();
+        true
     }
 }
 
@@ -70,11 +150,36 @@ impl<M: Clone + PartialEq + 'static> From<Button<M>> for Component<M> {
     }
 }
 
-impl<'a, M: Clone + PartialEq> ComponentModel<M> for Button<M> {
+impl<M: Clone + PartialEq> ComponentModel<M> for Button<M> {
     fn draw(&self, paint_brush: &mut dyn PaintBrush, font_map: &mut GCharMap) {
         self.button_label.draw(paint_brush, font_map)
     }
+    fn is_animating(&self) -> bool {
+        self.button_label.is_animating()
+    }
+    fn take_state(&mut self) -> Option<WidgetState> {
+        Some(Box::new(ButtonState {
+            hovered: self.hovered,
+            pressed: self.pressed,
+            label: self.button_label.take_state(),
+        }))
+    }
+    fn restore_state(&mut self, state: WidgetState) {
+        if let Ok(state) = state.downcast::<ButtonState>() {
+            self.hovered = state.hovered;
+            self.pressed = state.pressed && !self.disabled;
+            self.button_label.restore_state(state.label);
+            // 新布局中的样式或禁用状态可能已变化
+            self.update_interaction();
+        }
+    }
     fn listener(&mut self, event_context: &mut dyn EventContext<M>) -> bool {
+        // 禁用的按钮不声明悬停，下层组件仍可处于悬停状态，悬停状态变化时重绘
+        if self.disabled {
+            return std::mem::replace(&mut self.hovered, false);
+        }
+        let hover_listener =
+            component::hover_listener(event_context, &self.button_label.size, &mut self.hovered);
         let mut key_listener = false;
         let mut mouse_listener = false;
         let g_event = event_context.get_event();
@@ -90,6 +195,42 @@ impl<'a, M: Clone + PartialEq> ComponentModel<M> for Button<M> {
             }
             _ => {}
         }
-        key_listener || mouse_listener
+        if hover_listener || mouse_listener {
+            self.update_interaction();
+        }
+        hover_listener || key_listener || mouse_listener
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::runtime::testing;
+
+    fn moved() -> GEvent {
+        GEvent {
+            event: EventType::CursorMoved,
+            state: State::None,
+        }
+    }
+
+    #[test]
+    fn disabled_button_does_not_claim_hover() {
+        let (mut runtime, _receiver) = testing::runtime::<()>();
+        let mut button = Button::new(Point::new(0.0, 0.0), "ok");
+        runtime.set_cursor_pos(Point::new(5.0, 5.0));
+        runtime.set_event(moved());
+        assert!(button.listener(&mut runtime));
+        assert!(button.hovered);
+
+        // 禁用后失去悬停状态并请求重绘，悬停留给下层组件
+        button = button.disabled(true);
+        button.hovered = true;
+        runtime.set_event(moved());
+        assert!(button.listener(&mut runtime));
+        assert!(!button.hovered);
+        assert!(runtime.claim_hover());
+        runtime.set_event(moved());
+        assert!(!button.listener(&mut runtime));
     }
 }
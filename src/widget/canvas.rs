@@ -0,0 +1,220 @@
+use std::fmt::Formatter;
+
+use crate::event::*;
+use crate::graphic::base::*;
+use crate::graphic::render_api::PaintBrush;
+use crate::graphic::style::Style;
+use crate::widget::{Component, ComponentModel};
+
+/// 画布绘制回调
+pub type CanvasPaint = Box<dyn Fn(&mut CanvasPainter)>;
+/// 画布鼠标事件回调，参数为画布局部坐标下的鼠标位置及事件，返回需要发送的消息
+/// 手势事件中的坐标同样为局部坐标
+pub type CanvasMouse<M> = Box<dyn FnMut(Point<f32>, &GEvent) -> Option<M>>;
+
+/// 画布绘图接口
+/// 坐标原点为画布左上角，超出画布范围的内容被裁剪
+pub struct CanvasPainter<'a> {
+    paint_brush: &'a mut dyn PaintBrush,
+    font_map: &'a mut GCharMap,
+    size: Point<u32>,
+}
+
+impl CanvasPainter<'_> {
+    /// 获取画布尺寸
+    pub fn size(&self) -> Point<u32> {
+        self.size
+    }
+
+    /// 绘制图形
+    pub fn draw_shape<S: ShapeGraph + 'static>(&mut self, shape: S, style: Style) {
+        let shape: Box<dyn ShapeGraph> = Box::new(shape);
+        self.paint_brush.draw_shape(&shape, style);
+    }
+
+    /// 绘制文本
+    pub fn draw_text(&mut self, text_rect: &Rectangle, text: &str, text_color: RGBA) {
+        self.paint_brush
+            .draw_text(self.font_map, text_rect, text, text_color);
+    }
+
+    /// 绘制图像，每帧传入同一来源（或其克隆）时复用已上传的纹理
+    pub fn draw_image(&mut self, image_rect: &Rectangle, image: &ImageSource) {
+        self.paint_brush.draw_image(image_rect, image);
+    }
+
+    /// 压入变换，作用于画布局部坐标系
+    pub fn push_transform(&mut self, transform: Transform) {
+        self.paint_brush.push_transform(transform);
+    }
+
+    /// 弹出最近压入的变换
+    pub fn pop_transform(&mut self) {
+        self.paint_brush.pop_transform();
+    }
+}
+
+/// 即时绘制画布控件结构体
+/// 每次重绘时调用绘制回调
+pub struct Canvas<M> {
+    /// 画布区域
+    pub rect: Rectangle,
+    /// 绘制回调
+    painter: CanvasPaint,
+    /// 鼠标事件回调
+    on_mouse: Option<CanvasMouse<M>>,
+    /// 是否在画布内按下并捕获了指针
+    pressed: bool,
+}
+
+impl<M> Canvas<M> {
+    /// 创建画布
+    pub fn new<F>(rect: Rectangle, painter: F) -> Self
+    where
+        F: Fn(&mut CanvasPainter) + 'static,
+    {
+        Canvas {
+            rect,
+            painter: Box::new(painter),
+            on_mouse: None,
+            pressed: false,
+        }
+    }
+
+    /// 设置鼠标事件回调，画布范围内的鼠标按键、移动及手势事件会以局部坐标传入
+    /// 在画布内按下鼠标后捕获指针，松开前画布外的鼠标事件也会传入
+    pub fn on_mouse<F>(mut self, handler: F) -> Self
+    where
+        F: FnMut(Point<f32>, &GEvent) -> Option<M> + 'static,
+    {
+        self.on_mouse = Some(Box::new(handler));
+        self
+    }
+}
+
+impl<M> std::fmt::Debug for Canvas<M> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Canvas").field("rect", &self.rect).finish()
+    }
+}
+
+impl<M: Clone + PartialEq + 'static> From<Canvas<M>> for Component<M> {
+    fn from(canvas: Canvas<M>) -> Self {
+        Component::new(canvas)
+    }
+}
+
+impl<M> ComponentModel<M> for Canvas<M> {
+    fn draw(&self, paint_brush: &mut dyn PaintBrush, font_map: &mut GCharMap) {
+        let rect = self.rect;
+        paint_brush.push_transform(Transform::translation(rect.position.x, rect.position.y));
+        paint_brush.push_clip(Rectangle::new(0.0, 0.0, rect.width, rect.height));
+        let mut painter = CanvasPainter {
+            paint_brush: &mut *paint_brush,
+            font_map,
+            size: Point::new(rect.width, rect.height),
+        };
+        (self.painter)(&mut painter);
+        paint_brush.pop_clip();
+        paint_brush.pop_transform();
+    }
+
+    fn listener(&mut self, event_context: &mut dyn EventContext<M>) -> bool {
+        let handler = match &mut self.on_mouse {
+            Some(handler) => handler,
+            None => return false,
+        };
+        let g_event = event_context.get_event();
+        if !g_event.is_pointer() {
+            return false;
+        }
+        // 捕获在鼠标松开、手势分发完后才释放
+        if self.pressed && !event_context.is_pointer_captured() {
+            self.pressed = false;
+        }
+        let cursor = event_context.get_cursor_pos();
+        if !self.pressed && !self.rect.contain_coord(cursor) {
+            return false;
+        }
+        if matches!(g_event.event, EventType::Mouse(_)) && g_event.state == State::Pressed {
+            self.pressed = true;
+            event_context.capture_pointer();
+        }
+        let origin = self.rect.position;
+        let local = Point::new(cursor.x - origin.x, cursor.y - origin.y);
+        let local_event = match &g_event.event {
+            EventType::Gesture(gesture) => GEvent {
+                event: EventType::Gesture(gesture.translate(-origin.x, -origin.y)),
+                state: g_event.state,
+            },
+            _ => g_event,
+        };
+        if let Some(message) = handler(local, &local_event) {
+            event_context.send_message(message);
+        }
+        event_context.consume();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::backend::runtime::testing;
+
+    fn event(event: EventType, state: State) -> GEvent {
+        GEvent { event, state }
+    }
+
+    #[test]
+    fn pointer_events_are_local_and_captured_after_press() {
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let record = received.clone();
+        let mut canvas: Canvas<()> = Canvas::new(Rectangle::new(10.0, 20.0, 50, 50), |_| {})
+            .on_mouse(move |position, event| {
+                record.borrow_mut().push((position, event.event.clone()));
+                None
+            });
+        let (mut runtime, _receiver) = testing::runtime();
+
+        // 画布外的移动不传入
+        runtime.set_cursor_pos(Point::new(100.0, 100.0));
+        runtime.dispatch_event(&mut canvas, event(EventType::CursorMoved, State::None));
+        assert!(received.borrow().is_empty());
+
+        runtime.set_cursor_pos(Point::new(15.0, 25.0));
+        runtime.dispatch_event(
+            &mut canvas,
+            event(EventType::Mouse(Mouse::Left), State::Pressed),
+        );
+        assert!(runtime.is_pointer_captured());
+        // 按下后拖出画布仍然传入，坐标为局部坐标
+        runtime.set_cursor_pos(Point::new(100.0, 100.0));
+        runtime.dispatch_event(&mut canvas, event(EventType::CursorMoved, State::None));
+        runtime.dispatch_event(
+            &mut canvas,
+            event(EventType::Mouse(Mouse::Left), State::Released),
+        );
+        assert!(!runtime.is_pointer_captured());
+
+        let received = received.borrow();
+        assert_eq!(
+            received[0],
+            (Point::new(5.0, 5.0), EventType::Mouse(Mouse::Left))
+        );
+        assert_eq!(
+            received[1],
+            (Point::new(90.0, 80.0), EventType::CursorMoved)
+        );
+        assert!(received.contains(&(
+            Point::new(90.0, 80.0),
+            EventType::Gesture(Gesture::DragEnd {
+                start: Point::new(5.0, 5.0),
+                position: Point::new(90.0, 80.0),
+            })
+        )));
+    }
+}
@@ -1,9 +1,14 @@
+use std::any::Any;
 use std::fmt::Formatter;
 
-use crate::event::{EventContext, State};
+use crate::event::EventContext;
 use crate::graphic::base::{GCharMap, Rectangle};
 use crate::graphic::render_api::PaintBrush;
-use crate::graphic::style::Style;
+use crate::instance::Instance;
+use crate::widget::Map;
+
+/// 组件持久状态，布局重建时由旧组件转移到新组件
+pub type WidgetState = Box<dyn Any>;
 
 /// 组件模型trait
 /// 作用：定义组件必须的公共方法接口
@@ -13,46 +18,86 @@ pub trait ComponentModel<M> {
     fn listener(&mut self, _event_context: &mut dyn EventContext<M>) -> bool {
         false
     }
+    /// 组件是否有正在进行的动画，有则事件循环会持续重绘
+    fn is_animating(&self) -> bool {
+        false
+    }
+    /// 取出需要在布局重建后保留的内部状态，如焦点、光标位置、按下状态
+    fn take_state(&mut self) -> Option<WidgetState> {
+        None
+    }
+    /// 恢复旧组件取出的内部状态，状态类型不匹配时忽略
+    fn restore_state(&mut self, _state: WidgetState) {}
 }
 
 /// 封装组件接口
 pub struct Component<M> {
     pub(crate) widget: Box<dyn ComponentModel<M>>,
+    /// 组件标识，布局重建时按标识匹配新旧组件，未设置时按位置匹配
+    pub(crate) key: Option<String>,
 }
 
 impl<M: Clone + PartialEq> Component<M> {
     pub fn new(widget: impl ComponentModel<M> + 'static) -> Component<M> {
         Component {
             widget: Box::new(widget),
+            key: None,
+        }
+    }
+
+    /// 设置组件标识，组件位置可能变化时用于保留其内部状态
+    pub fn key<K: Into<String>>(mut self, key: K) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// 将组件发送的消息映射为父组件的消息，用于组合消息类型不同的子组件
+    pub fn map<P, F>(self, map: F) -> Component<P>
+    where
+        M: Send + 'static,
+        P: Send + 'static,
+        F: Fn(M) -> P + Send + Sync + 'static,
+    {
+        Component {
+            widget: Box::new(Map::new(self.widget, map)),
+            key: self.key,
+        }
+    }
+
+    /// 嵌入子实例，子实例的消息映射为父实例的消息，见`Map::instance`
+    pub fn instance<I, P, F>(child: &I, map: F) -> Component<P>
+    where
+        M: Send + 'static,
+        I: Instance<M = M>,
+        P: Send + 'static,
+        F: Fn(M) -> P + Send + Sync + 'static,
+    {
+        Component {
+            widget: Box::new(Map::instance(child, map)),
+            key: None,
         }
     }
 }
 
 impl<M> std::fmt::Debug for Component<M> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("").finish()
+        f.debug_struct("").field("key", &self.key).finish()
     }
 }
 
-/// 键鼠单击动画效果
-pub fn action_animation<M>(
-    event_context: &dyn EventContext<M>,
-    style: &mut Style,
+/// 更新组件的悬停状态，返回悬停状态是否变化
+/// 只在鼠标事件中更新，多个组件重叠时只有最上层的组件处于悬停状态
+pub fn hover_listener<M>(
+    event_context: &mut dyn EventContext<M>,
     position: &Rectangle,
-    message: Option<M>,
+    hovered: &mut bool,
 ) -> bool {
-    let input = position.contain_coord(event_context.get_cursor_pos());
-    if input && message.is_some() {
-        let message = message.unwrap();
-        let hover_color = style.get_hover_color();
-        let back_color = style.get_back_color();
-        if event_context.get_event().state == State::Pressed {
-            style.display_color(hover_color);
-            event_context.send_message(message);
-        } else if event_context.get_event().state == State::Released {
-            style.display_color(back_color);
-        }
-        return true;
+    if !event_context.get_event().is_pointer() {
+        return false;
     }
-    return false;
+    let hover =
+        position.contain_coord(event_context.get_cursor_pos()) && event_context.claim_hover();
+    let changed = hover != *hovered;
+    *hovered = hover;
+    changed
 }
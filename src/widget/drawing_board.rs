@@ -1,26 +1,575 @@
+use crate::event::*;
 use crate::graphic::base::*;
 use crate::graphic::render_api::PaintBrush;
 use crate::graphic::style::Style;
-use crate::widget::{Component, ComponentModel};
+use crate::widget::{Component, ComponentModel, WidgetState};
+
+/// 选中框及控制点颜色
+const SELECTION_COLOR: RGBA = RGBA(0.0, 0.47, 0.84, 1.0);
+/// 控制点边长
+const HANDLE_SIZE: f32 = 8.0;
+/// 宽高都小于此尺寸的新图形视为误触，不保留
+const MIN_SHAPE_SIZE: f32 = 2.0;
+/// 点击描边时允许的偏差
+const HIT_TOLERANCE: f32 = 3.0;
+
+/// 画板编辑工具
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BoardTool {
+    /// 选择、移动、缩放图形
+    Select,
+    /// 手绘线条
+    Freehand,
+    /// 绘制矩形
+    Rectangle,
+    /// 绘制圆，起点为圆心
+    Circle,
+}
+
+/// 画板中可编辑的图形
+#[derive(Debug, Clone)]
+pub enum BoardShape {
+    Stroke(Polyline),
+    Rect(Rectangle),
+    Circle(Circle),
+}
+
+/// 画板图形及其样式
+#[derive(Debug, Clone)]
+pub struct BoardItem {
+    pub shape: BoardShape,
+    pub style: Style,
+}
+
+/// 鼠标拖动状态
+#[derive(Debug, Copy, Clone)]
+enum Drag {
+    /// 绘制新图形，记录起点
+    Draw(Point<f32>),
+    /// 移动选中图形，记录上一次鼠标位置
+    Move(Point<f32>),
+    /// 拖动控制点缩放选中图形，记录固定的对角点
+    Resize(Point<f32>),
+}
+
+/// 画板在布局重建后需要保留的编辑状态
+struct BoardState {
+    selected: Option<usize>,
+    drag: Option<Drag>,
+    dirty: bool,
+    focused: bool,
+    /// 已编辑的图形列表
+    items: Vec<BoardItem>,
+    /// 图形列表版本
+    revision: u64,
+}
+
+/// 图形外接矩形
+#[derive(Debug, Copy, Clone)]
+struct Bounds {
+    min: Point<f32>,
+    max: Point<f32>,
+}
+
+impl Bounds {
+    fn from_corners(a: Point<f32>, b: Point<f32>) -> Bounds {
+        Bounds {
+            min: Point::new(a.x.min(b.x), a.y.min(b.y)),
+            max: Point::new(a.x.max(b.x), a.y.max(b.y)),
+        }
+    }
+
+    fn width(&self) -> f32 {
+        self.max.x - self.min.x
+    }
+
+    fn height(&self) -> f32 {
+        self.max.y - self.min.y
+    }
+
+    fn contains(&self, p: Point<f32>) -> bool {
+        p.x >= self.min.x && p.x <= self.max.x && p.y >= self.min.y && p.y <= self.max.y
+    }
+
+    /// 四个角点，依次为左上、右上、右下、左下
+    fn corners(&self) -> [Point<f32>; 4] {
+        [
+            self.min,
+            Point::new(self.max.x, self.min.y),
+            self.max,
+            Point::new(self.min.x, self.max.y),
+        ]
+    }
+
+    fn to_rect(self) -> Rectangle {
+        Rectangle::new(
+            self.min.x,
+            self.min.y,
+            self.width() as u32,
+            self.height() as u32,
+        )
+    }
+}
+
+fn distance(a: Point<f32>, b: Point<f32>) -> f32 {
+    ((a.x - b.x).powi(2) + (a.y - b.y).powi(2)).sqrt()
+}
+
+/// 点到线段的距离
+fn segment_distance(p: Point<f32>, a: Point<f32>, b: Point<f32>) -> f32 {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let length = dx * dx + dy * dy;
+    if length <= f32::EPSILON {
+        return distance(p, a);
+    }
+    let t = (((p.x - a.x) * dx + (p.y - a.y) * dy) / length).clamp(0.0, 1.0);
+    distance(p, Point::new(a.x + t * dx, a.y + t * dy))
+}
+
+impl BoardShape {
+    /// 获取外接矩形
+    fn bounds(&self) -> Bounds {
+        match self {
+            BoardShape::Stroke(line) => {
+                let first = line.points.first().copied().unwrap_or_default();
+                line.points
+                    .iter()
+                    .fold(Bounds::from_corners(first, first), |bounds, p| Bounds {
+                        min: Point::new(bounds.min.x.min(p.x), bounds.min.y.min(p.y)),
+                        max: Point::new(bounds.max.x.max(p.x), bounds.max.y.max(p.y)),
+                    })
+            }
+            BoardShape::Rect(rect) => Bounds::from_corners(
+                rect.position,
+                Point::new(
+                    rect.position.x + rect.width as f32,
+                    rect.position.y + rect.height as f32,
+                ),
+            ),
+            BoardShape::Circle(circle) => {
+                let (c, r) = (circle.position, circle.radius);
+                Bounds::from_corners(Point::new(c.x - r, c.y - r), Point::new(c.x + r, c.y + r))
+            }
+        }
+    }
+
+    /// 判断点是否落在图形上
+    fn hit(&self, p: Point<f32>) -> bool {
+        match self {
+            BoardShape::Stroke(line) => {
+                let tolerance = line.stroke.width / 2.0 + HIT_TOLERANCE;
+                match line.points.len() {
+                    0 => false,
+                    1 => distance(p, line.points[0]) <= tolerance,
+                    _ => line
+                        .points
+                        .windows(2)
+                        .any(|s| segment_distance(p, s[0], s[1]) <= tolerance),
+                }
+            }
+            BoardShape::Rect(_) => self.bounds().contains(p),
+            BoardShape::Circle(circle) => distance(p, circle.position) <= circle.radius,
+        }
+    }
+
+    /// 平移图形
+    fn translate(&mut self, dx: f32, dy: f32) {
+        let offset = |p: &mut Point<f32>| {
+            p.x += dx;
+            p.y += dy;
+        };
+        match self {
+            BoardShape::Stroke(line) => line.points.iter_mut().for_each(offset),
+            BoardShape::Rect(rect) => offset(&mut rect.position),
+            BoardShape::Circle(circle) => offset(&mut circle.position),
+        }
+    }
+
+    /// 将图形由原外接矩形缩放到新外接矩形，圆保持为圆
+    fn set_bounds(&mut self, old: Bounds, new: Bounds) {
+        match self {
+            BoardShape::Stroke(line) => {
+                let scale_x = if old.width() > 0.0 {
+                    new.width() / old.width()
+                } else {
+                    1.0
+                };
+                let scale_y = if old.height() > 0.0 {
+                    new.height() / old.height()
+                } else {
+                    1.0
+                };
+                for p in line.points.iter_mut() {
+                    p.x = new.min.x + (p.x - old.min.x) * scale_x;
+                    p.y = new.min.y + (p.y - old.min.y) * scale_y;
+                }
+            }
+            BoardShape::Rect(rect) => *rect = new.to_rect(),
+            BoardShape::Circle(circle) => {
+                circle.position =
+                    Point::new((new.min.x + new.max.x) / 2.0, (new.min.y + new.max.y) / 2.0);
+                circle.radius = new.width().min(new.height()) / 2.0;
+            }
+        }
+    }
+
+    fn to_graph(&self) -> Box<dyn ShapeGraph> {
+        match self {
+            BoardShape::Stroke(line) => Box::new(line.clone()),
+            BoardShape::Rect(rect) => Box::new(*rect),
+            BoardShape::Circle(circle) => Box::new(*circle),
+        }
+    }
+}
+
+/// 图形列表变化时的回调，返回需要发送的消息
+type ChangeHandler<M> = Box<dyn Fn(&[BoardItem]) -> Option<M>>;
 
 /// 图形绘制面板控件结构体
-pub struct ShapeBoard {
+/// 设置编辑区域后可用鼠标绘制、选择、移动、缩放图形，
+/// 在编辑区域内点击获得焦点后，按Delete或Backspace删除选中图形
+pub struct ShapeBoard<M = ()> {
+    /// 静态图形，不可编辑
     pub shape_arr: Vec<Box<dyn ShapeGraph>>,
+    /// 第一个静态图形的样式
     pub style: Style,
+    /// 编辑区域，为None时不响应鼠标
+    pub rect: Option<Rectangle>,
+    /// 可编辑图形列表
+    pub items: Vec<BoardItem>,
+    /// 图形列表版本，布局重建时版本变化则以布局中设置的列表为准
+    revision: u64,
+    /// 当前编辑工具
+    pub tool: BoardTool,
+    /// 新建图形的样式
+    pub item_style: Style,
+    /// 手绘线条的描边样式
+    pub stroke: Stroke,
+    /// 选中图形的索引
+    selected: Option<usize>,
+    drag: Option<Drag>,
+    /// 拖动过程中图形是否发生变化
+    dirty: bool,
+    /// 是否获得焦点，获得焦点时才响应键盘
+    focused: bool,
+    /// 图形列表变化时的回调，返回需要发送的消息
+    on_change: Option<ChangeHandler<M>>,
 }
 
-impl<M: Clone + PartialEq + 'static> From<ShapeBoard> for Component<M> {
-    fn from(shape_board: ShapeBoard) -> Self {
+impl<M> ShapeBoard<M> {
+    pub fn new(shape_arr: Vec<Box<dyn ShapeGraph>>, style: Style) -> Self {
+        ShapeBoard {
+            shape_arr,
+            style,
+            rect: None,
+            items: Vec::new(),
+            revision: 0,
+            tool: BoardTool::Select,
+            item_style: Style::default(),
+            stroke: Stroke::new(2.).cap(LineCap::Round).join(LineJoin::Round),
+            selected: None,
+            drag: None,
+            dirty: false,
+            focused: false,
+            on_change: None,
+        }
+    }
+
+    /// 设置编辑区域，开启鼠标编辑
+    pub fn editable(mut self, rect: Rectangle) -> Self {
+        self.rect = Some(rect);
+        self
+    }
+
+    /// 设置编辑工具
+    pub fn tool(mut self, tool: BoardTool) -> Self {
+        self.tool = tool;
+        self
+    }
+
+    /// 设置初始的可编辑图形列表
+    /// 布局重建时保留画板中已编辑的图形，版本不变时重建后的布局中设置的列表不生效
+    pub fn items(mut self, items: Vec<BoardItem>) -> Self {
+        self.items = items;
+        self
+    }
+
+    /// 设置图形列表版本，用于加载保存的图形
+    /// 布局重建时版本与旧画板不同，则以布局中设置的列表替换已编辑的图形，并清除选中状态
+    pub fn revision(mut self, revision: u64) -> Self {
+        self.revision = revision;
+        self
+    }
+
+    /// 设置新建图形的样式
+    pub fn item_style(mut self, style: Style) -> Self {
+        self.item_style = style;
+        self
+    }
+
+    /// 设置手绘线条的描边样式
+    pub fn stroke(mut self, stroke: Stroke) -> Self {
+        self.stroke = stroke;
+        self
+    }
+
+    /// 设置图形列表变化时的回调，在一次绘制、移动、缩放或删除完成后调用
+    pub fn on_change<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&[BoardItem]) -> Option<M> + 'static,
+    {
+        self.on_change = Some(Box::new(handler));
+        self
+    }
+
+    /// 鼠标按下，开始绘制或选择图形
+    fn press(&mut self, cursor: Point<f32>) {
+        let shape = match self.tool {
+            BoardTool::Select => {
+                // 优先检查选中图形的控制点
+                if let Some(index) = self.selected {
+                    let corners = self.items[index].shape.bounds().corners();
+                    let handle = corners
+                        .iter()
+                        .position(|c| distance(*c, cursor) <= HANDLE_SIZE);
+                    if let Some(i) = handle {
+                        self.drag = Some(Drag::Resize(corners[(i + 2) % 4]));
+                        return;
+                    }
+                }
+                self.selected = self.items.iter().rposition(|item| item.shape.hit(cursor));
+                self.drag = self.selected.map(|_| Drag::Move(cursor));
+                return;
+            }
+            BoardTool::Freehand => {
+                BoardShape::Stroke(Polyline::new(vec![cursor], self.stroke.clone()))
+            }
+            BoardTool::Rectangle => BoardShape::Rect(Rectangle::new(cursor.x, cursor.y, 0, 0)),
+            BoardTool::Circle => BoardShape::Circle(Circle::new(cursor.x, cursor.y, 0.)),
+        };
+        self.items.push(BoardItem {
+            shape,
+            style: self.item_style,
+        });
+        self.selected = Some(self.items.len() - 1);
+        self.drag = Some(Drag::Draw(cursor));
+    }
+
+    /// 鼠标拖动，更新绘制中或选中的图形
+    fn drag_to(&mut self, cursor: Point<f32>) {
+        let (index, drag) = match (self.selected, self.drag) {
+            (Some(index), Some(drag)) => (index, drag),
+            _ => return,
+        };
+        let shape = &mut self.items[index].shape;
+        match drag {
+            Drag::Draw(origin) => match shape {
+                BoardShape::Stroke(line) => line.points.push(cursor),
+                BoardShape::Rect(rect) => *rect = Bounds::from_corners(origin, cursor).to_rect(),
+                BoardShape::Circle(circle) => circle.radius = distance(origin, cursor),
+            },
+            Drag::Move(last) => {
+                shape.translate(cursor.x - last.x, cursor.y - last.y);
+                self.drag = Some(Drag::Move(cursor));
+            }
+            Drag::Resize(anchor) => {
+                let new = Bounds::from_corners(anchor, cursor);
+                if new.width() < MIN_SHAPE_SIZE || new.height() < MIN_SHAPE_SIZE {
+                    return;
+                }
+                let old = shape.bounds();
+                shape.set_bounds(old, new);
+            }
+        }
+        self.dirty = true;
+    }
+
+    /// 鼠标松开，结束拖动，返回图形列表是否发生变化
+    fn release(&mut self) -> bool {
+        if let (Some(Drag::Draw(_)), Some(index)) = (self.drag.take(), self.selected) {
+            let bounds = self.items[index].shape.bounds();
+            if bounds.width() < MIN_SHAPE_SIZE && bounds.height() < MIN_SHAPE_SIZE {
+                self.items.remove(index);
+                self.selected = None;
+                self.dirty = false;
+            }
+        }
+        std::mem::replace(&mut self.dirty, false)
+    }
+
+    /// 删除选中图形
+    fn delete_selected(&mut self) -> bool {
+        match self.selected.take() {
+            Some(index) => {
+                self.items.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 绘制选中框及四角控制点
+    fn draw_selection(&self, paint_brush: &mut dyn PaintBrush, item: &BoardItem) {
+        let corners = item.shape.bounds().corners();
+        let frame: Box<dyn ShapeGraph> =
+            Box::new(Polyline::new(corners.to_vec(), Stroke::new(1.)).close());
+        paint_brush.draw_shape(&frame, Style::default().back_color(SELECTION_COLOR));
+        let handle_style = Style::default().back_color(WHITE).border(SELECTION_COLOR);
+        for corner in corners {
+            let handle: Box<dyn ShapeGraph> = Box::new(Rectangle::new(
+                corner.x - HANDLE_SIZE / 2.0,
+                corner.y - HANDLE_SIZE / 2.0,
+                HANDLE_SIZE as u32,
+                HANDLE_SIZE as u32,
+            ));
+            paint_brush.draw_shape(&handle, handle_style);
+        }
+    }
+}
+
+impl<M: Clone + PartialEq + 'static> From<ShapeBoard<M>> for Component<M> {
+    fn from(shape_board: ShapeBoard<M>) -> Self {
         Component::new(shape_board)
     }
 }
 
-impl<M> ComponentModel<M> for ShapeBoard {
+impl<M> ComponentModel<M> for ShapeBoard<M> {
     fn draw(&self, paint_brush: &mut dyn PaintBrush, _font_map: &mut GCharMap) {
         let mut style = self.style;
         for shape in &self.shape_arr {
             paint_brush.draw_shape(shape, style);
             style = Style::default().back_color(LIGHT_BLUE).round();
         }
+        if let Some(rect) = self.rect {
+            paint_brush.push_clip(rect);
+        }
+        for item in &self.items {
+            paint_brush.draw_shape(&item.shape.to_graph(), item.style);
+        }
+        if let Some(index) = self.selected {
+            self.draw_selection(paint_brush, &self.items[index]);
+        }
+        if self.rect.is_some() {
+            paint_brush.pop_clip();
+        }
+    }
+
+    fn listener(&mut self, event_context: &mut dyn EventContext<M>) -> bool {
+        let rect = match self.rect {
+            Some(rect) => rect,
+            None => return false,
+        };
+        let g_event = event_context.get_event();
+        let cursor = event_context.get_cursor_pos();
+        let changed = match (g_event.event, g_event.state) {
+            (EventType::Mouse(Mouse::Left), State::Pressed) => {
+                // 点击编辑区域外时失去焦点
+                if !rect.contain_coord(cursor) {
+                    return std::mem::replace(&mut self.focused, false);
+                }
+                self.focused = true;
+                self.press(cursor);
+                // 拖动到编辑区域外时仍继续接收鼠标事件
+                event_context.capture_pointer();
+                event_context.consume();
+                return true;
+            }
+            (EventType::CursorMoved, _) if self.drag.is_some() => {
+                self.drag_to(cursor);
+                event_context.consume();
+                return true;
+            }
+            (EventType::Mouse(Mouse::Left), State::Released) if self.drag.is_some() => {
+                self.release()
+            }
+            (EventType::KeyBoard(Some(KeyCode::Delete | KeyCode::Backspace)), State::Pressed)
+                if self.focused && self.selected.is_some() =>
+            {
+                event_context.consume();
+                self.delete_selected()
+            }
+            _ => return false,
+        };
+        if changed {
+            if let Some(message) = self.on_change.as_ref().and_then(|f| f(&self.items)) {
+                event_context.send_message(message);
+            }
+        }
+        true
+    }
+
+    fn take_state(&mut self) -> Option<WidgetState> {
+        Some(Box::new(BoardState {
+            selected: self.selected.take(),
+            drag: self.drag.take(),
+            dirty: self.dirty,
+            focused: self.focused,
+            items: std::mem::take(&mut self.items),
+            revision: self.revision,
+        }))
+    }
+
+    fn restore_state(&mut self, state: WidgetState) {
+        let state = match state.downcast::<BoardState>() {
+            Ok(state) => *state,
+            Err(_) => return,
+        };
+        self.focused = state.focused;
+        // 版本变化表示加载了新的图形列表，旧列表的选中及拖动状态不再有效
+        if state.revision != self.revision {
+            return;
+        }
+        self.items = state.items;
+        self.selected = state.selected;
+        self.drag = state.drag;
+        self.dirty = state.dirty;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f32) -> BoardItem {
+        BoardItem {
+            shape: BoardShape::Rect(Rectangle::new(x, 0.0, 10, 10)),
+            style: Style::default(),
+        }
+    }
+
+    fn positions(board: &ShapeBoard) -> Vec<f32> {
+        board
+            .items
+            .iter()
+            .map(|item| item.shape.bounds().min.x)
+            .collect()
+    }
+
+    /// 模拟布局重建，将旧画板的状态转移到新画板
+    fn rebuild(old: &mut ShapeBoard, new: ShapeBoard) -> ShapeBoard {
+        let mut new = new;
+        new.restore_state(old.take_state().unwrap());
+        new
+    }
+
+    fn board() -> ShapeBoard {
+        ShapeBoard::new(Vec::new(), Style::default())
+    }
+
+    #[test]
+    fn rebuild_keeps_edited_items() {
+        let mut old = board().items(vec![rect(0.0)]);
+        old.items.push(rect(20.0));
+        let new = rebuild(&mut old, board().items(vec![rect(0.0)]));
+        assert_eq!(positions(&new), vec![0.0, 20.0]);
+    }
+
+    #[test]
+    fn new_revision_replaces_items() {
+        let mut old = board().items(vec![rect(0.0), rect(20.0)]);
+        old.selected = Some(1);
+        let new = rebuild(&mut old, board().items(vec![rect(40.0)]).revision(1));
+        assert_eq!(positions(&new), vec![40.0]);
+        assert_eq!(new.selected, None);
     }
 }
@@ -1,58 +1,267 @@
-use crate::event::EventContext;
+use crate::event::{EventContext, InstanceId};
 use crate::graphic::base::GCharMap;
 use crate::graphic::render_api::PaintBrush;
 use crate::instance::*;
 use crate::widget::*;
 
+/// 帧中注册的实例及其布局
+struct FrameEntry<M: PartialEq + Clone, I> {
+    id: InstanceId,
+    instance: I,
+    panel: Panel<M>,
+}
+
 /// 窗口帧结构体
-/// 作用：用作gui控件的容器
+/// 作用：用作gui控件的容器，按注册顺序绘制多个实例，
+/// 消息只交给发送它的实例处理，没有目标的消息广播给所有实例
 pub struct Frame<M: PartialEq + Clone, I: Instance<M = M>> {
-    pub display_panel: Vec<(I, Panel<M>)>,
+    entries: Vec<FrameEntry<M, I>>,
+    next_id: InstanceId,
+    /// 已添加、尚未启动定时器及订阅的实例
+    added: Vec<InstanceId>,
+    /// 已移除、尚未停止订阅的实例
+    removed: Vec<InstanceId>,
+    /// 捕获了指针的实例
+    captured: Option<InstanceId>,
 }
 
 impl<M: Clone + PartialEq, I: Instance<M = M>> Frame<M, I> {
     pub fn new() -> Self {
         Self {
-            display_panel: Vec::new(),
+            entries: Vec::new(),
+            next_id: 0,
+            added: Vec::new(),
+            removed: Vec::new(),
+            captured: None,
         }
     }
 
-    pub fn add_instance(&mut self, instance: I) {
-        let layout = instance.layout();
-        self.display_panel.push((instance, layout));
+    /// 注册实例，返回实例id，后注册的实例绘制在上层
+    /// 其定时器及订阅在下一次处理事件时启动，消息发送给该实例
+    pub fn add_instance(&mut self, instance: I) -> InstanceId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.added.push(id);
+        let panel = instance.layout();
+        self.entries.push(FrameEntry {
+            id,
+            instance,
+            panel,
+        });
+        id
+    }
+
+    /// 移除实例，其订阅及定时器在下一次处理事件时停止
+    pub fn remove_instance(&mut self, id: InstanceId) -> Option<I> {
+        let index = self.entries.iter().position(|entry| entry.id == id)?;
+        self.removed.push(id);
+        Some(self.entries.remove(index).instance)
+    }
+
+    /// 获取实例
+    pub fn instance(&self, id: InstanceId) -> Option<&I> {
+        self.entries
+            .iter()
+            .find(|entry| entry.id == id)
+            .map(|entry| &entry.instance)
+    }
+
+    /// 按绘制顺序获取所有实例id
+    pub fn instance_ids(&self) -> Vec<InstanceId> {
+        self.entries.iter().map(|entry| entry.id).collect()
     }
 }
 
-impl<M: Clone + PartialEq, I: Instance<M = M>> ComponentModel<M> for Frame<M, I> {
+impl<M: Clone + PartialEq, I: Instance<M = M>> Default for Frame<M, I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M: Clone + PartialEq + Send + 'static, I: Instance<M = M>> ComponentModel<M> for Frame<M, I> {
     fn draw(&self, paint_brush: &mut dyn PaintBrush, font_map: &mut GCharMap) {
-        for (_, panel) in &self.display_panel {
-            panel.draw(paint_brush, font_map)
+        for entry in &self.entries {
+            entry.panel.draw(paint_brush, font_map)
         }
     }
 
     fn listener(&mut self, event_context: &mut dyn EventContext<M>) -> bool {
         let mut is_update = false;
-        let mut updated_instance: Vec<(I, Panel<M>)> = Vec::with_capacity(self.display_panel.len());
-        let mut updated_index = Vec::with_capacity(self.display_panel.len());
-        let mut i = 0;
-        for (instance, panel) in self.display_panel.as_mut_slice() {
-            if panel.listener(event_context) {
+        for id in self.removed.drain(..) {
+            event_context.cancel_instance_subscriptions(id);
+            event_context.cancel_instance_timers(id);
+        }
+        for id in self.added.drain(..) {
+            let Some(entry) = self.entries.iter().find(|entry| entry.id == id) else {
+                continue;
+            };
+            event_context.set_route(Some(id));
+            for timer in entry.instance.timers() {
+                event_context.add_timer(timer);
+            }
+            event_context.subscribe(entry.instance.subscription());
+        }
+        // 将消息交给目标实例处理，原位重建其布局，保持绘制顺序
+        if let Some(message) = event_context.get_message().cloned() {
+            let target = event_context.get_message_target();
+            // 清除消息，防止重复发送
+            event_context.set_message(None);
+            for entry in self
+                .entries
+                .iter_mut()
+                .filter(|entry| target.is_none_or(|id| id == entry.id))
+            {
+                event_context.set_route(Some(entry.id));
+                let command = entry.instance.update(&message);
+                event_context.spawn(command);
+                // 重建布局，并保留旧组件的内部状态
+                let mut panel = entry.instance.layout();
+                panel.reconcile(&mut entry.panel);
+                entry.panel = panel;
+                // 状态更新后重新比对订阅
+                event_context.subscribe(entry.instance.subscription());
                 is_update = true;
             }
-            if event_context.get_message().is_some() {
-                instance.update(event_context.get_message().unwrap());
-                updated_index.push(i);
-                // 清除消息，防止重复发送
-                event_context.set_message(None);
+            if !is_update {
+                log::warn!("message {:?} has no target instance", target);
             }
-            i += 1;
         }
-        for index in updated_index {
-            let (instance, _) = self.display_panel.remove(index);
-            let panel = instance.layout();
-            updated_instance.push((instance, panel));
+        if !event_context.is_pointer_captured() {
+            self.captured = None;
         }
-        self.display_panel.append(&mut updated_instance);
+        let captured = self
+            .captured
+            .filter(|_| event_context.get_event().is_pointer());
+        // 后注册的实例位于上层，优先处理事件，指针被捕获时鼠标事件只交给捕获的实例
+        for entry in self.entries.iter_mut().rev() {
+            if captured.is_some_and(|id| id != entry.id) {
+                continue;
+            }
+            let was_captured = event_context.is_pointer_captured();
+            event_context.set_route(Some(entry.id));
+            if entry.panel.listener(event_context) {
+                is_update = true;
+            }
+            if !was_captured && event_context.is_pointer_captured() {
+                self.captured = Some(entry.id);
+            }
+            if event_context.is_consumed() {
+                break;
+            }
+        }
+        event_context.set_route(None);
         is_update
     }
+
+    fn is_animating(&self) -> bool {
+        self.entries.iter().any(|entry| entry.panel.is_animating())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::stream;
+
+    use super::*;
+    use crate::backend::runtime::testing;
+    use crate::event::{Command, EventType, GEvent, State, Subscription, Timer};
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Message {
+        Tick,
+        Streamed,
+    }
+
+    struct Counter {
+        ticks: u32,
+    }
+
+    impl Instance for Counter {
+        type M = Message;
+
+        fn new() -> Self {
+            Counter { ticks: 0 }
+        }
+
+        fn layout(&self) -> Panel<Message> {
+            Panel::new()
+        }
+
+        fn update(&mut self, message: &Message) -> Command<Message> {
+            if *message == Message::Tick {
+                self.ticks += 1;
+            }
+            Command::none()
+        }
+
+        fn setting() -> Setting {
+            Setting::default()
+        }
+
+        fn timers(&self) -> Vec<Timer<Message>> {
+            vec![Timer::every(Duration::from_millis(1), Message::Tick)]
+        }
+
+        fn subscription(&self) -> Subscription<Message> {
+            Subscription::run("streamed", || stream::once(async { Message::Streamed }))
+        }
+    }
+
+    fn other() -> GEvent {
+        GEvent {
+            event: EventType::Other,
+            state: State::None,
+        }
+    }
+
+    fn ticks(frame: &Frame<Message, Counter>, id: InstanceId) -> u32 {
+        frame.instance(id).unwrap().ticks
+    }
+
+    #[test]
+    fn added_instances_start_timers_and_subscriptions() {
+        let (mut runtime, receiver) = testing::runtime();
+        let mut frame = Frame::new();
+        let first = frame.add_instance(Counter::new());
+        runtime.start(&mut frame);
+        // 事件循环开始后添加的实例在下一次处理事件时启动
+        let second = frame.add_instance(Counter::new());
+        runtime.dispatch_event(&mut frame, other());
+
+        let mut targets: Vec<_> = (0..2)
+            .map(|_| receiver.recv_timeout(Duration::from_secs(5)).unwrap())
+            .map(|envelope| {
+                assert_eq!(envelope.message, Message::Streamed);
+                envelope.target
+            })
+            .collect();
+        targets.sort();
+        assert_eq!(targets, vec![Some(first), Some(second)]);
+
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(runtime.fire_timers(&mut frame));
+        assert_eq!(ticks(&frame, first), 1);
+        assert_eq!(ticks(&frame, second), 1);
+    }
+
+    #[test]
+    fn removed_instances_stop_timers() {
+        let (mut runtime, _receiver) = testing::runtime();
+        let mut frame = Frame::new();
+        let first = frame.add_instance(Counter::new());
+        let second = frame.add_instance(Counter::new());
+        runtime.start(&mut frame);
+        frame.remove_instance(second);
+        runtime.dispatch_event(&mut frame, other());
+
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(runtime.fire_timers(&mut frame));
+        assert_eq!(ticks(&frame, first), 1);
+        frame.remove_instance(first);
+        runtime.dispatch_event(&mut frame, other());
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(!runtime.fire_timers(&mut frame));
+    }
 }
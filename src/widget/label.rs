@@ -1,10 +1,20 @@
 use std::fmt::Debug;
 use std::option::Option::Some;
+use std::time::Duration;
 
+use crate::graphic::animation::{Easing, Tween};
 use crate::graphic::base::*;
 use crate::graphic::render_api::PaintBrush;
 use crate::graphic::style::*;
 
+/// 面板在布局重建后需要保留的显示状态
+#[derive(Debug, Copy, Clone)]
+pub struct LabelState {
+    display_color: RGBA,
+    animation: Option<Tween<Style>>,
+    size_animation: Option<Tween<Rectangle>>,
+}
+
 /// 控件面板结构体
 #[derive(Debug)]
 pub struct Label {
@@ -15,7 +25,11 @@ pub struct Label {
     /// 面板文本
     pub text: Option<String>,
     /// 面板图像
-    pub image_path: Option<String>,
+    pub image: Option<ImageSource>,
+    /// 样式过渡动画，颜色（包括透明度）、边框、圆角及阴影平滑过渡
+    pub animation: Option<Tween<Style>>,
+    /// 位置及尺寸过渡动画，只影响绘制，点击范围始终为目标尺寸
+    pub size_animation: Option<Tween<Rectangle>>,
 }
 
 impl Label {
@@ -26,36 +40,100 @@ impl Label {
             size: rect,
             style,
             text: Some(text),
-            image_path: None,
+            image: None,
+            animation: None,
+            size_animation: None,
         }
     }
     /// 创建图像面板
-    pub fn new_image_label(rect: Rectangle, style: Style, image: String) -> Self {
+    pub fn new_image_label<I: Into<ImageSource>>(rect: Rectangle, style: Style, image: I) -> Self {
         log::info!("create image label");
         Self {
             size: rect,
             style,
             text: None,
-            image_path: Some(image),
+            image: Some(image.into()),
+            animation: None,
+            size_animation: None,
         }
     }
+    /// 获取当前显示的样式，动画进行中时为过渡中的样式
+    pub fn current_style(&self) -> Style {
+        match &self.animation {
+            Some(animation) => animation.value(),
+            None => self.style,
+        }
+    }
+    /// 从当前显示的样式过渡到目标样式
+    pub fn animate(&mut self, to: Style, duration: Duration, easing: Easing) {
+        self.animation = Some(Tween::new(self.current_style(), to, duration, easing));
+        self.style = to;
+    }
+    /// 获取当前显示的位置及尺寸，动画进行中时为过渡中的尺寸
+    pub fn current_size(&self) -> Rectangle {
+        match &self.size_animation {
+            Some(animation) => animation.value(),
+            None => self.size,
+        }
+    }
+    /// 从当前显示的位置及尺寸过渡到目标位置及尺寸
+    pub fn animate_size(&mut self, to: Rectangle, duration: Duration, easing: Easing) {
+        self.size_animation = Some(Tween::new(self.current_size(), to, duration, easing));
+        self.size = to;
+    }
+    /// 取消样式及尺寸动画，直接显示目标样式及尺寸
+    pub fn cancel_animation(&mut self) {
+        self.animation = None;
+        self.size_animation = None;
+    }
+    /// 样式或尺寸动画是否正在进行
+    pub fn is_animating(&self) -> bool {
+        let style = self
+            .animation
+            .is_some_and(|animation| !animation.is_finished());
+        let size = self
+            .size_animation
+            .is_some_and(|animation| !animation.is_finished());
+        style || size
+    }
+    /// 取出当前显示状态，包括显示颜色及正在进行的样式、尺寸动画
+    pub fn take_state(&mut self) -> LabelState {
+        LabelState {
+            display_color: self.style.get_display_color(),
+            animation: self.animation.take(),
+            size_animation: self.size_animation.take(),
+        }
+    }
+    /// 恢复显示状态，样式的其余部分保持不变
+    pub fn restore_state(&mut self, state: LabelState) {
+        self.style.display_color(state.display_color);
+        self.animation = state.animation;
+        self.size_animation = state.size_animation;
+    }
     /// 绘制label
     pub fn draw(&self, paint_brush: &mut dyn PaintBrush, font_map: &mut GCharMap) {
-        let shape: Box<dyn ShapeGraph> = Box::new(self.size);
-        paint_brush.draw_shape(&shape, self.style);
+        let style = self.current_style();
+        let size = self.current_size();
+        let shape: Box<dyn ShapeGraph> = Box::new(size);
+        paint_brush.draw_shape(&shape, style);
+        // 图像和文本不超出label的范围
+        paint_brush.push_clip(size);
+        let mut text_rect = size;
+        if let Some(image) = &self.image {
+            log::info!("draw label's image");
+            let mut image_rect = size;
+            // 同时有文本时，图像作为图标显示在左侧正方形区域
+            if self.text.is_some() {
+                image_rect.width = image_rect.height.min(image_rect.width);
+                text_rect.position.x += image_rect.width as f32;
+                text_rect.width -= image_rect.width;
+            }
+            paint_brush.draw_image(&image_rect, image)
+        }
         if let Some(text) = &self.text {
             log::info!("draw label's text");
-            paint_brush.draw_text(
-                font_map,
-                &self.size,
-                text.as_str(),
-                self.style.get_font_color(),
-            );
-        }
-        if let Some(image_path) = &self.image_path {
-            log::info!("draw label's image");
-            let img = ImageRaw::new(image_path.as_str());
-            paint_brush.draw_image(&self.size, img)
+            paint_brush.draw_text(font_map, &text_rect, text.as_str(), style.get_font_color());
         }
+        paint_brush.pop_clip();
     }
 }
@@ -0,0 +1,416 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use crate::event::*;
+use crate::graphic::base::{GCharMap, Point};
+use crate::graphic::render_api::PaintBrush;
+use crate::instance::Instance;
+use crate::widget::{ComponentModel, WidgetState};
+
+/// 消息映射函数
+pub type MessageMap<C, P> = Arc<dyn Fn(C) -> P + Send + Sync>;
+
+/// 消息映射组件结构体
+/// 将消息类型为C的子组件嵌入消息类型为P的父组件中，子组件发送的消息经映射后发送给父组件
+/// 子组件声明的订阅记录在独立的作用域中，与父实例自身的订阅互不影响
+pub struct Map<C, P> {
+    widget: Box<dyn ComponentModel<C>>,
+    map: MessageMap<C, P>,
+    /// 订阅作用域
+    scope: SubscriptionScope,
+    /// 嵌入的子实例声明的订阅及定时器
+    child: Option<ChildInstance<C>>,
+}
+
+/// 嵌入的子实例声明的订阅及定时器
+struct ChildInstance<C> {
+    /// 布局重建后尚未比对的订阅
+    subscription: Option<Subscription<C>>,
+    /// 尚未注册的定时器
+    timers: Vec<Timer<C>>,
+    /// 定时器是否已注册，布局重建时由旧组件转移
+    started: bool,
+}
+
+/// 嵌入子实例的映射组件在布局重建时保留的状态
+struct ChildState {
+    started: bool,
+    widget: Option<WidgetState>,
+}
+
+/// 计算订阅作用域
+fn scope_of<K: Hash>(key: K) -> SubscriptionScope {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl<C, P> Map<C, P> {
+    pub fn new<F>(widget: Box<dyn ComponentModel<C>>, map: F) -> Self
+    where
+        F: Fn(C) -> P + Send + Sync + 'static,
+    {
+        Map {
+            widget,
+            map: Arc::new(map),
+            scope: scope_of(std::any::type_name::<C>()),
+            child: None,
+        }
+    }
+
+    /// 嵌入子实例，子实例的布局作为子组件，其订阅及定时器的消息经映射后发送给父实例
+    /// 父实例在`update`中将映射后的消息转交给子实例，布局重建时订阅保持运行，定时器只注册一次
+    pub fn instance<I, F>(child: &I, map: F) -> Self
+    where
+        C: Clone + PartialEq + 'static,
+        I: Instance<M = C>,
+        F: Fn(C) -> P + Send + Sync + 'static,
+    {
+        Map {
+            widget: Box::new(child.layout()),
+            map: Arc::new(map),
+            scope: scope_of(std::any::type_name::<I>()),
+            child: Some(ChildInstance {
+                subscription: Some(child.subscription()),
+                timers: child.timers(),
+                started: false,
+            }),
+        }
+    }
+
+    /// 设置订阅作用域，同一父组件中嵌入多个同类型子实例时用于区分其订阅
+    pub fn scope<K: Hash>(mut self, key: K) -> Self {
+        self.scope = scope_of(key);
+        self
+    }
+}
+
+impl<C, P> std::fmt::Debug for Map<C, P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Map").finish()
+    }
+}
+
+impl<C: Send + 'static, P: Send + 'static> ComponentModel<P> for Map<C, P> {
+    fn draw(&self, paint_brush: &mut dyn PaintBrush, font_map: &mut GCharMap) {
+        self.widget.draw(paint_brush, font_map)
+    }
+
+    fn listener(&mut self, event_context: &mut dyn EventContext<P>) -> bool {
+        let mut mapped = MappedContext {
+            parent: event_context,
+            map: &self.map,
+            scope: self.scope,
+        };
+        if let Some(child) = &mut self.child {
+            if let Some(subscription) = child.subscription.take() {
+                mapped.subscribe(subscription);
+            }
+            if !child.started {
+                child.started = true;
+                for timer in child.timers.drain(..) {
+                    mapped.add_timer(timer);
+                }
+            }
+        }
+        self.widget.listener(&mut mapped)
+    }
+
+    fn is_animating(&self) -> bool {
+        self.widget.is_animating()
+    }
+
+    fn take_state(&mut self) -> Option<WidgetState> {
+        match &self.child {
+            Some(child) => Some(Box::new(ChildState {
+                started: child.started,
+                widget: self.widget.take_state(),
+            })),
+            None => self.widget.take_state(),
+        }
+    }
+
+    fn restore_state(&mut self, state: WidgetState) {
+        let Some(child) = &mut self.child else {
+            return self.widget.restore_state(state);
+        };
+        if let Ok(state) = state.downcast::<ChildState>() {
+            if state.started {
+                child.started = true;
+                child.timers.clear();
+            }
+            if let Some(widget) = state.widget {
+                self.widget.restore_state(widget);
+            }
+        }
+    }
+}
+
+/// 子组件的事件上下文
+/// 事件及窗口状态取自父组件的上下文，发送的消息经映射后由父组件的上下文发送
+struct MappedContext<'a, 'b, C, P> {
+    parent: &'a mut (dyn EventContext<P> + 'b),
+    map: &'a MessageMap<C, P>,
+    /// 子组件的订阅作用域
+    scope: SubscriptionScope,
+}
+
+impl<C: Send + 'static, P: Send + 'static> MappedContext<'_, '_, C, P> {
+    /// 获取可跨线程使用的映射函数
+    fn map_fn(&self) -> impl Fn(C) -> P + Clone + Send + 'static {
+        let map = self.map.clone();
+        move |message| map(message)
+    }
+}
+
+impl<C: Send + 'static, P: Send + 'static> EventContext<C> for MappedContext<'_, '_, C, P> {
+    fn set_cursor_pos(&mut self, pos: Point<f32>) {
+        self.parent.set_cursor_pos(pos)
+    }
+
+    fn get_cursor_pos(&self) -> Point<f32> {
+        self.parent.get_cursor_pos()
+    }
+
+    fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.parent.set_scale_factor(scale_factor)
+    }
+
+    fn get_scale_factor(&self) -> f32 {
+        self.parent.get_scale_factor()
+    }
+
+    fn set_cursor_icon(&mut self, cursor: Cursor) {
+        self.parent.set_cursor_icon(cursor)
+    }
+
+    fn claim_hover(&mut self) -> bool {
+        self.parent.claim_hover()
+    }
+
+    fn set_ime_position(&mut self) {
+        self.parent.set_ime_position()
+    }
+
+    fn set_event(&mut self, event: GEvent) {
+        self.parent.set_event(event)
+    }
+
+    fn get_event(&self) -> GEvent {
+        self.parent.get_event()
+    }
+
+    fn consume(&mut self) {
+        self.parent.consume()
+    }
+
+    fn is_consumed(&self) -> bool {
+        self.parent.is_consumed()
+    }
+
+    fn capture_pointer(&mut self) {
+        self.parent.capture_pointer()
+    }
+
+    fn release_pointer(&mut self) {
+        self.parent.release_pointer()
+    }
+
+    fn is_pointer_captured(&self) -> bool {
+        self.parent.is_pointer_captured()
+    }
+
+    /// 父组件的消息无法转换为子组件的消息，子组件总是获取不到消息
+    fn get_message(&self) -> Option<&C> {
+        None
+    }
+
+    fn set_message(&mut self, message: Option<C>) {
+        if let Some(message) = message {
+            self.parent.set_message(Some((self.map)(message)));
+        }
+    }
+
+    fn get_message_target(&self) -> Option<InstanceId> {
+        self.parent.get_message_target()
+    }
+
+    fn set_route(&mut self, target: Option<InstanceId>) {
+        self.parent.set_route(target)
+    }
+
+    fn send_message(&self, message: C) {
+        self.parent.send_message((self.map)(message))
+    }
+
+    fn spawn(&mut self, command: Command<C>) {
+        let map = self.map_fn();
+        self.parent.spawn(command.map(map))
+    }
+
+    fn request_capture(&mut self, path: String) {
+        self.parent.request_capture(path)
+    }
+
+    /// 子组件的订阅记录在其作用域中，不替换父实例自身的订阅
+    fn subscribe(&mut self, subscription: Subscription<C>) {
+        let map = self.map_fn();
+        self.parent
+            .subscribe_scope(self.scope, subscription.map(map))
+    }
+
+    /// 嵌套的作用域与当前作用域组合，保证各层子组件的订阅互不影响
+    fn subscribe_scope(&mut self, scope: SubscriptionScope, subscription: Subscription<C>) {
+        let map = self.map_fn();
+        self.parent
+            .subscribe_scope(scope_of((self.scope, scope)), subscription.map(map))
+    }
+
+    fn cancel_instance_subscriptions(&mut self, id: InstanceId) {
+        self.parent.cancel_instance_subscriptions(id)
+    }
+
+    fn add_timer(&mut self, timer: Timer<C>) -> TimerId {
+        let timer = timer.map(|message| (self.map)(message));
+        self.parent.add_timer(timer)
+    }
+
+    fn cancel_timer(&mut self, id: TimerId) {
+        self.parent.cancel_timer(id)
+    }
+
+    fn cancel_instance_timers(&mut self, id: InstanceId) {
+        self.parent.cancel_instance_timers(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::stream;
+
+    use super::*;
+    use crate::backend::runtime::testing;
+    use crate::instance::Setting;
+    use crate::widget::Panel;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Parent {
+        Own,
+        Child(u32),
+    }
+
+    /// 每次处理事件都发送消息的子组件
+    struct Emit;
+
+    impl ComponentModel<u32> for Emit {
+        fn draw(&self, _paint_brush: &mut dyn PaintBrush, _font_map: &mut GCharMap) {}
+
+        fn listener(&mut self, event_context: &mut dyn EventContext<u32>) -> bool {
+            event_context.send_message(1);
+            false
+        }
+    }
+
+    struct Child;
+
+    impl Instance for Child {
+        type M = u32;
+
+        fn new() -> Self {
+            Child
+        }
+
+        fn layout(&self) -> Panel<u32> {
+            Panel::new()
+        }
+
+        fn setting() -> Setting {
+            Setting::default()
+        }
+
+        fn timers(&self) -> Vec<Timer<u32>> {
+            vec![Timer::after(Duration::ZERO, 2)]
+        }
+
+        fn subscription(&self) -> Subscription<u32> {
+            Subscription::run("shared", || stream::once(async { 3 }))
+        }
+    }
+
+    /// 父组件，记录收到的消息
+    struct Host {
+        map: Map<u32, Parent>,
+        received: Vec<Parent>,
+    }
+
+    impl ComponentModel<Parent> for Host {
+        fn draw(&self, _paint_brush: &mut dyn PaintBrush, _font_map: &mut GCharMap) {}
+
+        fn listener(&mut self, event_context: &mut dyn EventContext<Parent>) -> bool {
+            if let Some(message) = event_context.get_message().cloned() {
+                event_context.set_message(None);
+                self.received.push(message);
+            }
+            self.map.listener(event_context)
+        }
+    }
+
+    fn other() -> GEvent {
+        GEvent {
+            event: EventType::Other,
+            state: State::None,
+        }
+    }
+
+    #[test]
+    fn child_messages_are_mapped() {
+        let (mut runtime, receiver) = testing::runtime();
+        let mut map = Map::new(Box::new(Emit), Parent::Child);
+        runtime.dispatch_event(&mut map, other());
+        let envelope = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(envelope.message, Parent::Child(1));
+    }
+
+    #[test]
+    fn parent_and_child_subscriptions_run_side_by_side() {
+        let (mut runtime, receiver) = testing::runtime();
+        let parent = || Subscription::run("shared", || stream::once(async { Parent::Own }));
+        runtime.set_route(Some(0));
+        runtime.subscribe(parent());
+        let mut map = Map::instance(&Child, Parent::Child);
+        runtime.dispatch_event(&mut map, other());
+        // 父实例重新声明订阅不会停止子实例的订阅
+        runtime.subscribe(parent());
+
+        let mut messages: Vec<_> = (0..2)
+            .map(|_| receiver.recv_timeout(Duration::from_secs(5)).unwrap())
+            .map(|envelope| {
+                assert_eq!(envelope.target, Some(0));
+                envelope.message
+            })
+            .collect();
+        messages.sort_by_key(|message| matches!(message, Parent::Child(_)));
+        assert_eq!(messages, vec![Parent::Own, Parent::Child(3)]);
+    }
+
+    #[test]
+    fn child_timers_are_registered_once() {
+        let (mut runtime, _receiver) = testing::runtime();
+        let mut host = Host {
+            map: Map::instance(&Child, Parent::Child),
+            received: Vec::new(),
+        };
+        runtime.dispatch_event(&mut host, other());
+        // 布局重建后定时器不会重复注册
+        let mut rebuilt = Map::instance(&Child, Parent::Child);
+        rebuilt.restore_state(host.map.take_state().unwrap());
+        host.map = rebuilt;
+        runtime.dispatch_event(&mut host, other());
+
+        assert!(runtime.fire_timers(&mut host));
+        assert_eq!(host.received, vec![Parent::Child(2)]);
+    }
+}
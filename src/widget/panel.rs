@@ -1,25 +1,65 @@
+use std::collections::HashMap;
+use std::fmt::Formatter;
+
 use crate::event::EventContext;
 use crate::graphic::base::GCharMap;
 use crate::graphic::render_api::PaintBrush;
-use crate::widget::{Component, ComponentModel};
+use crate::widget::{Component, ComponentModel, WidgetState};
+
+/// 面板事件处理回调，返回是否需要重绘，可调用`EventContext::consume`阻止事件继续传递
+pub type PanelHandler<M> = Box<dyn FnMut(&mut dyn EventContext<M>) -> bool>;
 
 /// 容器面板结构体
-#[derive(Debug)]
+/// 事件先经过捕获阶段交给面板，再按绘制顺序的逆序交给子组件（上层组件优先），
+/// 最后在冒泡阶段交给面板，任一阶段事件被处理后不再继续传递
 pub struct Panel<M>
 where
     M: PartialEq,
     M: std::clone::Clone,
 {
     pub widgets: Vec<Component<M>>,
+    /// 捕获阶段回调，先于子组件处理事件
+    capture: Option<PanelHandler<M>>,
+    /// 冒泡阶段回调，处理子组件未处理的事件
+    bubble: Option<PanelHandler<M>>,
+    /// 捕获了指针的子组件下标
+    captured: Option<usize>,
+}
+
+impl<M: Clone + PartialEq> Default for Panel<M> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<M: Clone + PartialEq> Panel<M> {
     pub fn new() -> Panel<M> {
         Panel {
             widgets: Vec::with_capacity(4),
+            capture: None,
+            bubble: None,
+            captured: None,
         }
     }
 
+    /// 设置捕获阶段回调
+    pub fn on_capture<F>(mut self, handler: F) -> Self
+    where
+        F: FnMut(&mut dyn EventContext<M>) -> bool + 'static,
+    {
+        self.capture = Some(Box::new(handler));
+        self
+    }
+
+    /// 设置冒泡阶段回调
+    pub fn on_bubble<F>(mut self, handler: F) -> Self
+    where
+        F: FnMut(&mut dyn EventContext<M>) -> bool + 'static,
+    {
+        self.bubble = Some(Box::new(handler));
+        self
+    }
+
     pub fn push<E>(mut self, child: E) -> Self
     where
         E: Into<Component<M>>,
@@ -27,21 +67,153 @@ impl<M: Clone + PartialEq> Panel<M> {
         self.widgets.push(child.into());
         self
     }
+
+    /// 添加带标识的子组件
+    pub fn push_keyed<K, E>(self, key: K, child: E) -> Self
+    where
+        K: Into<String>,
+        E: Into<Component<M>>,
+    {
+        self.push(child.into().key(key))
+    }
+
+    /// 将旧面板中组件的内部状态转移到当前面板
+    /// 有标识的组件按标识匹配，无标识的组件按其在无标识组件中的次序匹配
+    pub fn reconcile(&mut self, old: &mut Panel<M>) {
+        if let Some(state) = old.take_state() {
+            self.restore_state(state);
+        }
+    }
+}
+
+impl<M: Clone + PartialEq> std::fmt::Debug for Panel<M> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Panel")
+            .field("widgets", &self.widgets)
+            .finish()
+    }
 }
 
-impl<'a, M: Clone + PartialEq> ComponentModel<M> for Panel<M> {
+impl<M: Clone + PartialEq + 'static> From<Panel<M>> for Component<M> {
+    fn from(panel: Panel<M>) -> Self {
+        Component::new(panel)
+    }
+}
+
+/// 子组件在面板中的位置，有标识的组件按标识，无标识的组件按其在无标识组件中的次序
+#[derive(Debug, Clone, PartialEq)]
+enum ChildSlot {
+    Keyed(String),
+    Unkeyed(usize),
+}
+
+/// 面板中各子组件的状态
+struct PanelState {
+    keyed: HashMap<String, WidgetState>,
+    unkeyed: Vec<Option<WidgetState>>,
+    /// 捕获了指针的子组件
+    captured: Option<ChildSlot>,
+}
+
+impl<M: Clone + PartialEq> ComponentModel<M> for Panel<M> {
     fn draw(&self, paint_brush: &mut dyn PaintBrush, font_map: &mut GCharMap) {
         for widget in &self.widgets {
             widget.widget.draw(paint_brush, font_map);
         }
     }
-    fn listener(&mut self, _event_context: &mut dyn EventContext<M>) -> bool {
+    fn listener(&mut self, event_context: &mut dyn EventContext<M>) -> bool {
+        // 指针捕获已被释放
+        if !event_context.is_pointer_captured() {
+            self.captured = None;
+        }
+        // 指针被子组件捕获时，鼠标事件只交给该组件
+        if let Some(index) = self.captured {
+            if event_context.get_event().is_pointer() && index < self.widgets.len() {
+                return self.widgets[index].widget.listener(event_context);
+            }
+        }
         let mut is_listener = false;
-        for comp in &mut self.widgets {
-            if comp.widget.listener(_event_context) {
+        if let Some(capture) = &mut self.capture {
+            is_listener = capture(event_context);
+            if event_context.is_consumed() {
+                return is_listener;
+            }
+        }
+        // 后绘制的组件位于上层，优先处理事件
+        for (index, comp) in self.widgets.iter_mut().enumerate().rev() {
+            let captured = event_context.is_pointer_captured();
+            if comp.widget.listener(event_context) {
+                is_listener = true;
+            }
+            if !captured && event_context.is_pointer_captured() {
+                self.captured = Some(index);
+            }
+            if event_context.is_consumed() {
+                return is_listener;
+            }
+        }
+        if let Some(bubble) = &mut self.bubble {
+            if bubble(event_context) {
                 is_listener = true;
             }
         }
-        return is_listener;
+        is_listener
+    }
+    fn is_animating(&self) -> bool {
+        self.widgets.iter().any(|comp| comp.widget.is_animating())
+    }
+    fn take_state(&mut self) -> Option<WidgetState> {
+        let captured = self.captured.take();
+        let mut state = PanelState {
+            keyed: HashMap::new(),
+            unkeyed: Vec::with_capacity(self.widgets.len()),
+            captured: None,
+        };
+        for (index, comp) in self.widgets.iter_mut().enumerate() {
+            if captured == Some(index) {
+                state.captured = Some(match &comp.key {
+                    Some(key) => ChildSlot::Keyed(key.clone()),
+                    None => ChildSlot::Unkeyed(state.unkeyed.len()),
+                });
+            }
+            let widget_state = comp.widget.take_state();
+            match &comp.key {
+                Some(key) => {
+                    if let Some(widget_state) = widget_state {
+                        if state.keyed.insert(key.clone(), widget_state).is_some() {
+                            log::warn!("duplicate component key: {}", key);
+                        }
+                    }
+                }
+                None => state.unkeyed.push(widget_state),
+            }
+        }
+        Some(Box::new(state))
+    }
+    fn restore_state(&mut self, state: WidgetState) {
+        let PanelState {
+            mut keyed,
+            unkeyed,
+            captured,
+        } = match state.downcast::<PanelState>() {
+            Ok(state) => *state,
+            Err(_) => return,
+        };
+        let mut unkeyed = unkeyed.into_iter().enumerate();
+        for (index, comp) in self.widgets.iter_mut().enumerate() {
+            let (slot, widget_state) = match &comp.key {
+                Some(key) => (ChildSlot::Keyed(key.clone()), keyed.remove(key)),
+                None => match unkeyed.next() {
+                    Some((order, widget_state)) => (ChildSlot::Unkeyed(order), widget_state),
+                    None => continue,
+                },
+            };
+            if captured.as_ref() == Some(&slot) {
+                self.captured = Some(index);
+            }
+            if let Some(widget_state) = widget_state {
+                comp.widget.restore_state(widget_state);
+            }
+        }
     }
 }
@@ -14,9 +14,21 @@ pub struct TextInput<M: Clone> {
     pub text_receive: Box<dyn Fn(String) -> M>,
     ///是否聚焦
     pub is_focus: bool,
+    /// 鼠标是否悬停
+    hovered: bool,
+    /// 光标位置，即光标前的字符数
+    pub caret: usize,
 }
 
-impl<'a, M: Clone + PartialEq> TextInput<M> {
+/// 输入框在布局重建后需要保留的状态
+#[derive(Debug, Copy, Clone)]
+struct TextInputState {
+    is_focus: bool,
+    caret: usize,
+    label: LabelState,
+}
+
+impl<M: Clone + PartialEq> TextInput<M> {
     pub fn new_with_style<S: Into<String>, MT>(
         rect: Rectangle,
         style: Style,
@@ -26,10 +38,13 @@ impl<'a, M: Clone + PartialEq> TextInput<M> {
     where
         MT: 'static + Fn(String) -> M,
     {
+        let text = text.into();
         Self {
-            text_label: Label::new_text_label(rect, style, text.into()),
+            caret: text.chars().count(),
+            text_label: Label::new_text_label(rect, style, text),
             state: None,
             is_focus: false,
+            hovered: false,
             text_receive: Box::new(rec),
         }
     }
@@ -42,38 +57,71 @@ impl<'a, M: Clone + PartialEq> TextInput<M> {
         let rect = Rectangle::new(pos.x, pos.y, (text.len() * 10) as u32 + 10, 40);
         let style = Style::default().back_color(WHITE);
         Self {
+            caret: text.chars().count(),
             text_label: Label::new_text_label(rect, style, text),
             state: None,
             text_receive: Box::new(rec),
             is_focus: false,
+            hovered: false,
         }
     }
-    fn hover_listener(&mut self, event_context: &mut dyn EventContext<M>) -> bool {
-        let input = self
-            .text_label
-            .size
-            .contain_coord(event_context.get_cursor_pos());
-        if input {
-            event_context.set_cursor_icon(Cursor::Text);
-        } else {
-            event_context.set_cursor_icon(Cursor::Default);
-        }
-        input
+    /// 文本的字符数
+    fn char_count(&self) -> usize {
+        self.text_label
+            .text
+            .as_ref()
+            .map_or(0, |text| text.chars().count())
     }
     fn received_character(&mut self, event_context: &mut dyn EventContext<M>, c: char) -> bool {
-        if self.hover_listener(event_context) {
-            log::debug!("ime: {:?}", c);
-            if let Some(text) = &mut self.text_label.text {
-                if c == '\u{8}' {
-                    text.pop();
-                } else {
-                    text.push(c);
+        log::debug!("ime: {:?}", c);
+        let text = match &mut self.text_label.text {
+            Some(text) => text,
+            None => return false,
+        };
+        // 光标处的字节下标
+        let byte_index = |text: &String, caret: usize| {
+            text.char_indices()
+                .nth(caret)
+                .map_or(text.len(), |(index, _)| index)
+        };
+        match c {
+            // 退格
+            '\u{8}' => {
+                if self.caret == 0 {
+                    return false;
                 }
-                event_context.send_message((self.text_receive)(text.clone()));
+                self.caret -= 1;
+                text.remove(byte_index(text, self.caret));
+            }
+            // 删除
+            '\u{7f}' => {
+                if self.caret >= text.chars().count() {
+                    return false;
+                }
+                text.remove(byte_index(text, self.caret));
+            }
+            c if c.is_control() => return false,
+            c => {
+                text.insert(byte_index(text, self.caret), c);
+                self.caret += 1;
             }
         }
+        event_context.send_message((self.text_receive)(text.clone()));
         true
     }
+    /// 方向键等移动光标
+    fn move_caret(&mut self, key_code: KeyCode) -> bool {
+        let caret = match key_code {
+            KeyCode::Left => self.caret.saturating_sub(1),
+            KeyCode::Right => (self.caret + 1).min(self.char_count()),
+            KeyCode::Home => 0,
+            KeyCode::End => self.char_count(),
+            _ => return false,
+        };
+        let moved = caret != self.caret;
+        self.caret = caret;
+        moved
+    }
 }
 
 impl<M: Clone + PartialEq + 'static> From<TextInput<M>> for Component<M> {
@@ -82,19 +130,80 @@ impl<M: Clone + PartialEq + 'static> From<TextInput<M>> for Component<M> {
     }
 }
 
-impl<'a, M: Clone + PartialEq> ComponentModel<M> for TextInput<M> {
+impl<M: Clone + PartialEq> ComponentModel<M> for TextInput<M> {
     fn draw(&self, paint_brush: &mut dyn PaintBrush, font_map: &mut GCharMap) {
-        self.text_label.draw(paint_brush, font_map)
+        self.text_label.draw(paint_brush, font_map);
+        // 聚焦时在光标位置绘制竖线
+        if self.is_focus {
+            let rect = self.text_label.size;
+            let before_caret: String = self
+                .text_label
+                .text
+                .iter()
+                .flat_map(|text| text.chars().take(self.caret))
+                .collect();
+            let x = rect.position.x + 8. + font_map.text_width(&before_caret);
+            let caret: Box<dyn ShapeGraph> = Box::new(Rectangle::new(
+                x,
+                rect.position.y + 4.,
+                2,
+                rect.height.saturating_sub(8),
+            ));
+            let style = Style::default().back_color(self.text_label.style.get_font_color());
+            paint_brush.push_clip(rect);
+            paint_brush.draw_shape(&caret, style);
+            paint_brush.pop_clip();
+        }
     }
-    fn listener(&mut self, _event_context: &mut dyn EventContext<M>) -> bool {
-        let hover_listener = self.hover_listener(_event_context);
-        let g_event = _event_context.get_event();
+    fn is_animating(&self) -> bool {
+        self.text_label.is_animating()
+    }
+    fn listener(&mut self, event_context: &mut dyn EventContext<M>) -> bool {
+        let hover_listener =
+            component::hover_listener(event_context, &self.text_label.size, &mut self.hovered);
+        if self.hovered {
+            event_context.set_cursor_icon(Cursor::Text);
+        }
+        let g_event = event_context.get_event();
+        let mut changed = false;
         match g_event.event {
-            EventType::ReceivedCharacter(c) => {
-                self.received_character(_event_context, c);
+            // 单击输入框内聚焦，单击其他位置失焦
+            EventType::Mouse(Mouse::Left) if g_event.state == State::Pressed => {
+                changed = self.is_focus != self.hovered;
+                self.is_focus = self.hovered;
+                if self.is_focus {
+                    event_context.set_ime_position();
+                    event_context.consume();
+                }
+            }
+            // 聚焦时键盘输入只交给此输入框
+            EventType::ReceivedCharacter(c) if self.is_focus => {
+                changed = self.received_character(event_context, c);
+                event_context.consume();
+            }
+            EventType::KeyBoard(Some(key_code)) if self.is_focus => {
+                if g_event.state == State::Pressed {
+                    changed = self.move_caret(key_code);
+                }
+                event_context.consume();
             }
             _ => {}
         }
-        hover_listener
+        hover_listener || changed
+    }
+    fn take_state(&mut self) -> Option<WidgetState> {
+        Some(Box::new(TextInputState {
+            is_focus: self.is_focus,
+            caret: self.caret,
+            label: self.text_label.take_state(),
+        }))
+    }
+    fn restore_state(&mut self, state: WidgetState) {
+        if let Ok(state) = state.downcast::<TextInputState>() {
+            self.is_focus = state.is_focus;
+            // 新文本可能变短
+            self.caret = state.caret.min(self.char_count());
+            self.text_label.restore_state(state.label);
+        }
     }
 }